@@ -10,19 +10,47 @@ use super::serde::*;
 pub use parse::*;
 pub use load::*;
 
-#[derive(Deserialize, Default)]
-#[cfg_attr(test, derive(Debug, Clone, PartialEq, Serialize))]
+#[derive(Deserialize, Serialize, Default)]
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
 pub struct Config {
-    /// The required version of kitty
+    /// The required version of gg, e.g. `>=0.3`
     pub gg: Option<String>,
     #[serde(deserialize_with = "de_string_or_struct_hashmap", default = "HashMap::new")]
     pub scripts: HashMap<String, Script>,
+    /// Extensions required by the project
+    #[serde(deserialize_with = "de_string_or_struct_hashmap", default = "HashMap::new")]
+    pub extensions: HashMap<String, Extension>,
+    /// Tools pinned by the project
+    #[serde(default = "HashMap::new")]
+    pub tools: HashMap<String, Tool>,
+    /// Directories (relative to the root) prepended to PATH when running scripts,
+    /// or presets like `@node` for `node_modules/.bin`
+    #[serde(default)]
+    pub path: Vec<String>,
+    /// Short names of scripts, e.g. `{"t": "test"}`
+    #[serde(default = "HashMap::new")]
+    pub aliases: HashMap<String, String>,
 }
 
-#[derive(Deserialize, Default)]
-#[cfg_attr(test, derive(Debug, Clone, PartialEq, Serialize))]
+#[derive(Deserialize, Serialize, Default)]
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
 pub struct Script {
     pub command: String,
+    /// Like `Config::path`, but only for this script and takes precedence
+    #[serde(default)]
+    pub path: Vec<String>,
+    /// Command to run before `command`, the script fails without running `command` if it fails
+    pub before: Option<String>,
+    /// Command to run after `command` succeeds
+    pub after: Option<String>,
+    /// Command to run if the script fails, the exit code of the script is kept
+    pub on_failure: Option<String>,
+    /// Run `after` even if the script fails
+    #[serde(default)]
+    pub always: bool,
+    /// Other names of the script, like `Config::aliases`
+    #[serde(default)]
+    pub aliases: Vec<String>,
 }
 
 impl FromStr for Script {
@@ -31,6 +59,47 @@ impl FromStr for Script {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Script {
             command: s.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct Extension {
+    /// The semver requirement of the extension version, e.g. `^1.2`
+    pub version: Option<String>,
+    /// Where to install the extension from, see `gg ext install`
+    pub source: Option<String>,
+}
+
+impl FromStr for Extension {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Extension {
+            version: Some(s.to_string()),
+            source: None,
         })
     }
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct Tool {
+    /// The pinned version, also substituted for `{version}` in urls
+    pub version: String,
+    /// Downloads by platform, keyed by `<os>-<arch>` (e.g. `linux-x86_64`, `macos-aarch64`)
+    pub platforms: HashMap<String, ToolDownload>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[cfg_attr(test, derive(Debug, Clone, PartialEq))]
+pub struct ToolDownload {
+    /// The url of an archive (`.tar.gz`, `.tgz`, `.zip`) or a single executable, `file://` is supported
+    pub url: String,
+    /// The sha256 checksum of the download
+    pub sha256: String,
+    /// The path of the executable inside the archive, defaults to the tool name
+    pub bin: Option<String>,
 }
\ No newline at end of file
@@ -2,7 +2,8 @@ use std::path::{Path, PathBuf};
 use tokio::fs::read_to_string;
 use crate::{Config, parse};
 use crate::result::Result;
-use gg_git::G;
+use gg_git::{backend, Backend};
+use tracing::debug;
 
 /// load config from a file
 pub async fn load<P: AsRef<Path>>(path: P) -> Result<Config> {
@@ -40,21 +41,26 @@ pub async fn auto_load<P: Into<PathBuf>>(dir: P) -> Result<Option<LoadedConfig>>
 
     for filename in CONFIG_FILENAMES.iter() {
         let path = dir.join(filename);
-        let config = load(path.clone()).await;
-        if config.is_ok() {
-            return Ok(Some(LoadedConfig {
-                path,
-                config: config.unwrap(),
-            }));
-        } else {
-            let err = config.err().unwrap();
-            if !err.is_not_exist() {
-                return Err(err);
+        match load(path.clone()).await {
+            Ok(config) => {
+                debug!("loaded config {}", path.display());
+                return Ok(Some(LoadedConfig {
+                    path,
+                    config,
+                }));
+            }
+            Err(err) => {
+                if !err.is_not_exist() {
+                    debug!("cannot load config {}: {}", path.display(), err);
+                    return Err(err);
+                }
+                debug!("config {} does not exist", path.display());
             }
         }
     }
 
     // not found
+    debug!("no config file found in {}", dir.display());
     Ok(None)
 }
 
@@ -62,11 +68,12 @@ pub async fn auto_load<P: Into<PathBuf>>(dir: P) -> Result<Option<LoadedConfig>>
 pub async fn auto_load_for_repo<P: Into<PathBuf>>(dir: P) -> Result<Option<LoadedConfig>> {
     let dir = dir.into();
 
-    let root = G::new(&dir).root().await;
-    if root.is_err() {
-        // not a git repo or git is not available
-        return auto_load(dir).await;
+    match Backend::root(&backend(&dir)).await {
+        Ok(root) => auto_load(root).await,
+        Err(err) if err.is_not_repository() || err.is_git_not_found() => {
+            debug!("{}, looking for config in {}", err, dir.display());
+            auto_load(dir).await
+        }
+        Err(err) => Err(err.into()),
     }
-
-    auto_load(root.unwrap()).await
 }
\ No newline at end of file
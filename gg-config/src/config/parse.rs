@@ -8,6 +8,7 @@ pub fn parse(s: &str) -> Result<Config> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Extension, ToolDownload};
 
     #[test]
     fn test_parse_empty() {
@@ -16,4 +17,69 @@ mod tests {
 
         assert_eq!(config, Config::default());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_extensions() {
+        let s = r#"{"extensions": {"lint": "^1.2", "deploy": {"version": ">=0.3", "source": "https://example.com/gg-deploy.git"}}}"#;
+        let config = parse(s).unwrap();
+
+        assert_eq!(config.extensions.len(), 2);
+        assert_eq!(config.extensions["lint"], Extension { version: Some("^1.2".to_string()), source: None });
+        assert_eq!(config.extensions["deploy"], Extension {
+            version: Some(">=0.3".to_string()),
+            source: Some("https://example.com/gg-deploy.git".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_parse_tools() {
+        let s = r#"{"tools": {"protoc": {"version": "25.1", "platforms": {
+            "linux-x86_64": {"url": "https://example.com/protoc-{version}.zip", "sha256": "abc", "bin": "bin/protoc"}
+        }}}}"#;
+        let config = parse(s).unwrap();
+
+        let protoc = &config.tools["protoc"];
+        assert_eq!(protoc.version, "25.1");
+        assert_eq!(protoc.platforms["linux-x86_64"], ToolDownload {
+            url: "https://example.com/protoc-{version}.zip".to_string(),
+            sha256: "abc".to_string(),
+            bin: Some("bin/protoc".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let s = r#"{"path": ["@node", "tools/bin"], "scripts": {
+            "a": "echo a",
+            "b": {"command": "echo b", "path": ["@python"]}
+        }}"#;
+        let config = parse(s).unwrap();
+
+        assert_eq!(config.path, vec!["@node".to_string(), "tools/bin".to_string()]);
+        assert!(config.scripts["a"].path.is_empty());
+        assert_eq!(config.scripts["b"].path, vec!["@python".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_hooks() {
+        let s = r#"{"scripts": {"build": {
+            "command": "make", "before": "make clean", "after": "echo done", "on_failure": "echo failed", "always": true
+        }}}"#;
+        let config = parse(s).unwrap();
+
+        let build = &config.scripts["build"];
+        assert_eq!(build.before.as_deref(), Some("make clean"));
+        assert_eq!(build.after.as_deref(), Some("echo done"));
+        assert_eq!(build.on_failure.as_deref(), Some("echo failed"));
+        assert!(build.always);
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        let s = r#"{"aliases": {"t": "test"}, "scripts": {"build": {"command": "make", "aliases": ["b"]}}}"#;
+        let config = parse(s).unwrap();
+
+        assert_eq!(config.aliases["t"], "test");
+        assert_eq!(config.scripts["build"].aliases, vec!["b".to_string()]);
+    }
+}
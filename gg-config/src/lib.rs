@@ -3,3 +3,4 @@ mod serde;
 mod result;
 
 pub use config::*;
+pub use result::Error;
@@ -6,6 +6,8 @@ pub enum Error {
     IO(#[from] std::io::Error),
     #[error("json error")]
     Json(#[from] serde_json::Error),
+    #[error("git error: {0}")]
+    Git(#[from] gg_git::Error),
 }
 
 impl Error {
@@ -1,3 +1,4 @@
 mod string_or_struct;
 
+#[allow(unused_imports)]
 pub use self::string_or_struct::{de_string_or_struct, de_string_or_struct_hashmap};
\ No newline at end of file
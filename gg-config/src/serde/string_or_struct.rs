@@ -17,7 +17,7 @@ struct StringOrStruct<T>(PhantomData<fn() -> T>);
 
 impl<T> Clone for StringOrStruct<T> {
     fn clone(&self) -> Self {
-        Self(PhantomData)
+        *self
     }
 }
 
@@ -67,6 +67,7 @@ impl<'de, T> DeserializeSeed<'de> for StringOrStruct<T>
     }
 }
 
+#[allow(dead_code)]
 pub fn de_string_or_struct<'de, T, D>(deserializer: D) -> Result<T, D::Error>
     where
         T: Deserialize<'de> + FromStr<Err=Infallible>,
@@ -95,8 +96,8 @@ macro_rules! map_impl {
         $access:ident,
         $with_capacity:expr,
     ) => {
-        $(#[$attr])*
         paste! {
+            $(#[$attr])*
             struct [<$name Visitor>]<K, V $(, $typaram)*> (PhantomData<fn() -> $ty<K, V $(, $typaram)*>>);
 
             impl<'de, K, V $(, $typaram)*> Visitor<'de> for [<$name Visitor>]<K, V $(, $typaram)*>
@@ -131,7 +132,7 @@ macro_rules! map_impl {
 }
 
 map_impl! {
-    #[cfg_attr(doc_cfg, doc(cfg(any(feature = "std", feature = "alloc"))))]
+    #[allow(dead_code)]
     BTreeMap<K: Ord, V>,
     BTreeMap,
     map,
@@ -139,7 +140,6 @@ map_impl! {
 }
 
 map_impl! {
-    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     HashMap<K: Eq + Hash, V, S: BuildHasher + Default>,
     HashMap,
     map,
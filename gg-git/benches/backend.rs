@@ -0,0 +1,27 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use gg_git::{Backend, G};
+
+fn bench<B: Backend>(c: &mut Criterion, name: &str, backend: B) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("root", |b| b.iter(|| rt.block_on(backend.root()).unwrap()));
+    group.bench_function("status", |b| b.iter(|| rt.block_on(backend.status()).unwrap()));
+    group.bench_function("staged_files", |b| b.iter(|| rt.block_on(backend.staged_files()).unwrap()));
+    group.finish();
+}
+
+fn cli(c: &mut Criterion) {
+    bench(c, "cli", G::default());
+}
+
+#[cfg(feature = "gix")]
+fn native(c: &mut Criterion) {
+    bench(c, "native", gg_git::Native::new(std::env::current_dir().unwrap()));
+}
+
+#[cfg(not(feature = "gix"))]
+fn native(_: &mut Criterion) {}
+
+criterion_group!(benches, cli, native);
+criterion_main!(benches);
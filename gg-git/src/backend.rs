@@ -0,0 +1,62 @@
+#[cfg(feature = "gix")]
+mod native;
+
+use std::future::Future;
+use std::path::PathBuf;
+use crate::g::G;
+use crate::result::Result;
+use crate::status::StatusEntry;
+
+#[cfg(feature = "gix")]
+pub use native::Native;
+
+/// a working tree and the git dir of its checkout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkTree {
+    pub root: PathBuf,
+    /// `.git`, or `.git/worktrees/<name>` and `.git/modules/<name>` for worktrees and submodules
+    pub git_dir: PathBuf,
+}
+
+/// operations on the hot path of every `gg` invocation
+///
+/// `G` implements it by calling the git cli. With the `gix` feature, `Native` implements it
+/// in-process and becomes the default backend.
+pub trait Backend {
+    /// root of the working tree
+    fn root(&self) -> impl Future<Output=Result<PathBuf>> + Send;
+    /// root and git dir of the working tree, found at once
+    fn work_tree(&self) -> impl Future<Output=Result<WorkTree>> + Send;
+    /// unstaged and untracked files, see `G::status`
+    fn status(&self) -> impl Future<Output=Result<Vec<StatusEntry>>> + Send;
+    /// files staged for commit, see `G::staged_files`
+    fn staged_files(&self) -> impl Future<Output=Result<Vec<PathBuf>>> + Send;
+}
+
+impl Backend for G {
+    fn root(&self) -> impl Future<Output=Result<PathBuf>> + Send {
+        G::root(self)
+    }
+
+    fn work_tree(&self) -> impl Future<Output=Result<WorkTree>> + Send {
+        G::work_tree(self)
+    }
+
+    fn status(&self) -> impl Future<Output=Result<Vec<StatusEntry>>> + Send {
+        G::status(self)
+    }
+
+    fn staged_files(&self) -> impl Future<Output=Result<Vec<PathBuf>>> + Send {
+        G::staged_files(self)
+    }
+}
+
+#[cfg(feature = "gix")]
+pub type DefaultBackend = Native;
+#[cfg(not(feature = "gix"))]
+pub type DefaultBackend = G;
+
+/// create the default backend for a dir
+pub fn backend<P: Into<PathBuf>>(dir: P) -> DefaultBackend {
+    DefaultBackend::new(dir)
+}
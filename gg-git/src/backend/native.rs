@@ -0,0 +1,207 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use gix::bstr::ByteSlice;
+use gix::status::index_worktree::iter::{Item, Summary};
+use gix::status::UntrackedFiles;
+use tracing::debug;
+use crate::backend::{Backend, WorkTree};
+use crate::result::{Error, Result};
+use crate::status::{Change, StatusEntry};
+
+/// backend reading the repository in-process with gitoxide
+#[derive(Clone)]
+pub struct Native {
+    dir: PathBuf,
+}
+
+impl Native {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Native {
+        Native { dir: dir.into() }
+    }
+
+    /// run blocking gix operations on the repository without blocking the runtime
+    async fn with_repo<T, F>(&self, operation: &'static str, f: F) -> Result<T>
+        where
+            T: Send + 'static,
+            F: FnOnce(gix::Repository) -> Result<T> + Send + 'static,
+    {
+        let start = Instant::now();
+        let dir = self.dir.clone();
+        let result = tokio::task::spawn_blocking(move || f(discover(&dir)?))
+            .await
+            .map_err(|err| Error::Gix(Box::new(err)))?;
+
+        debug!(cwd = %self.dir.display(), ok = result.is_ok(), elapsed = ?start.elapsed(), "gix {}", operation);
+        result
+    }
+}
+
+fn discover(dir: &Path) -> Result<gix::Repository> {
+    use gix::discover::upwards::Error as Upwards;
+
+    match gix::ThreadSafeRepository::discover_with_environment_overrides(dir) {
+        Ok(repo) => Ok(repo.to_thread_local()),
+        Err(gix::discover::Error::Discover(
+                Upwards::NoGitRepository { .. }
+                | Upwards::NoGitRepositoryWithinCeiling { .. }
+                | Upwards::NoGitRepositoryWithinFs { .. }
+            )) => Err(Error::NotRepository {
+            cwd: dir.to_path_buf(),
+            exit: None,
+        }),
+        Err(err) => Err(gix_error(err)),
+    }
+}
+
+fn gix_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> Error {
+    Error::Gix(Box::new(err))
+}
+
+fn work_dir(repo: &gix::Repository) -> Result<PathBuf> {
+    match repo.work_dir() {
+        Some(dir) => Ok(dir.canonicalize()?),
+        None => Err(Error::Gix("this operation must be run in a work tree".into())),
+    }
+}
+
+fn to_path(path: &gix::bstr::BStr) -> Result<PathBuf> {
+    Ok(path.to_path().map_err(gix_error)?.to_path_buf())
+}
+
+impl Backend for Native {
+    fn root(&self) -> impl Future<Output=Result<PathBuf>> + Send {
+        self.with_repo("root", |repo| work_dir(&repo))
+    }
+
+    fn work_tree(&self) -> impl Future<Output=Result<WorkTree>> + Send {
+        self.with_repo("work_tree", |repo| Ok(WorkTree {
+            root: work_dir(&repo)?,
+            git_dir: repo.git_dir().canonicalize()?,
+        }))
+    }
+
+    fn status(&self) -> impl Future<Output=Result<Vec<StatusEntry>>> + Send {
+        self.with_repo("status", |repo| {
+            work_dir(&repo)?;
+
+            let iter = repo.status(gix::progress::Discard)
+                .map_err(gix_error)?
+                .untracked_files(UntrackedFiles::Files)
+                .index_worktree_rewrites(None)
+                .into_index_worktree_iter(Vec::new())
+                .map_err(gix_error)?;
+
+            let mut entries = Vec::new();
+            for item in iter {
+                let item = item.map_err(gix_error)?;
+                let change = match item.summary() {
+                    Some(Summary::Modified) => Change::Modified,
+                    Some(Summary::IntentToAdd) => Change::Added,
+                    Some(Summary::Removed) => Change::Deleted,
+                    Some(Summary::TypeChange) => Change::TypeChanged,
+                    Some(Summary::Conflict) => Change::Unmerged,
+                    Some(Summary::Added) => Change::Untracked,
+                    // rename tracking is disabled
+                    Some(Summary::Renamed) | Some(Summary::Copied) | None => continue,
+                };
+
+                let path = match &item {
+                    Item::Modification { rela_path, .. } => rela_path.as_ref(),
+                    Item::DirectoryContents { entry, .. } => entry.rela_path.as_ref(),
+                    Item::Rewrite { dirwalk_entry, .. } => dirwalk_entry.rela_path.as_ref(),
+                };
+
+                entries.push(StatusEntry {
+                    path: to_path(path)?,
+                    change,
+                });
+            }
+
+            Ok(entries)
+        })
+    }
+
+    fn staged_files(&self) -> impl Future<Output=Result<Vec<PathBuf>>> + Send {
+        self.with_repo("staged_files", |repo| {
+            let index = repo.index_or_empty().map_err(gix_error)?;
+
+            // an unborn HEAD has no files, so everything in the index is staged
+            let mut head = std::collections::HashMap::new();
+            if !repo.head().map_err(gix_error)?.is_unborn() {
+                let id = repo.head_tree_id().map_err(gix_error)?;
+                let tree = repo.find_object(id).map_err(gix_error)?.into_tree();
+                let mut recorder = gix::traverse::tree::Recorder::default();
+                tree.traverse().breadthfirst(&mut recorder).map_err(gix_error)?;
+                for entry in recorder.records {
+                    if !entry.mode.is_tree() {
+                        head.insert(entry.filepath, (entry.oid, entry.mode));
+                    }
+                }
+            }
+
+            let mut files = Vec::new();
+            for entry in index.entries() {
+                let path = entry.path(&index);
+                let staged = match head.get(path) {
+                    Some((oid, mode)) => *oid != entry.id || entry.mode.to_tree_entry_mode() != Some(*mode),
+                    None => true,
+                };
+                if staged {
+                    files.push(to_path(path)?);
+                }
+            }
+            files.dedup();
+
+            Ok(files)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::G;
+    use crate::status::tests::{expected_staged_files, expected_status, setup};
+
+    #[tokio::test]
+    async fn test_root() {
+        let dir = setup("native-root");
+        std::fs::create_dir_all(dir.join("a/b")).unwrap();
+
+        let root = Native::new(dir.join("a/b")).root().await.unwrap();
+        assert_eq!(root, dir);
+        assert_eq!(root, G::new(dir.join("a/b")).root().await.unwrap());
+
+        let work_tree = Native::new(dir.join("a/b")).work_tree().await.unwrap();
+        assert_eq!(work_tree.git_dir, dir.join(".git"));
+        assert_eq!(work_tree, G::new(dir.join("a/b")).work_tree().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_root_not_repository() {
+        let dir = std::env::temp_dir().join("gg-git-test-native-not-repository");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let err = Native::new(&dir).root().await.err().unwrap();
+        assert!(err.is_not_repository(), "unexpected error: {}", err);
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let dir = setup("native");
+
+        let mut status = Native::new(dir.join("new")).status().await.unwrap();
+        status.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(status, expected_status());
+    }
+
+    #[tokio::test]
+    async fn test_staged_files() {
+        let dir = setup("native-staged");
+
+        let mut files = Native::new(&dir).staged_files().await.unwrap();
+        files.sort();
+        assert_eq!(files, expected_staged_files());
+    }
+}
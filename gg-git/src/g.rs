@@ -1,8 +1,12 @@
-use std::env;
+use std::{env, io};
 use std::path::{PathBuf};
+use std::process::ExitStatus;
+use std::time::Instant;
 use tokio::process::Command;
-use crate::result::{Error, Result};
+use tracing::debug;
+use crate::result::{Error, ExitError, Result};
 
+#[derive(Clone)]
 pub struct G {
     pub(crate) dir: PathBuf,
 }
@@ -31,12 +35,49 @@ pub struct SuccessOutput {
 }
 
 impl G {
+    pub(crate) fn command(&self, args: &[String]) -> Command {
+        let mut command = Command::new("git");
+        command.current_dir(&self.dir).args(args);
+        command
+    }
+
+    /// convert the error of spawning git into `Error`
+    pub(crate) fn spawn_error(&self, err: io::Error) -> Error {
+        // spawn also fails with NotFound if the working directory doesn't exist
+        if err.kind() == io::ErrorKind::NotFound && self.dir.is_dir() {
+            Error::GitNotFound
+        } else {
+            Error::IO(err)
+        }
+    }
+
+    pub(crate) fn exit_error(&self, args: Vec<String>, status: ExitStatus, stdout: Vec<u8>, stderr: Vec<u8>) -> Error {
+        Error::from_exit(ExitError {
+            args,
+            cwd: self.dir.clone(),
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// log a finished git command
+    pub(crate) fn trace_exit(&self, args: &[String], status: Option<ExitStatus>, start: Instant) {
+        debug!(
+            cwd = %self.dir.display(),
+            status = ?status.and_then(|s| s.code()),
+            elapsed = ?start.elapsed(),
+            "git {}", args.join(" "),
+        );
+    }
+
     pub async fn run(&self, args: Vec<String>) -> Result<SuccessOutput> {
-        let output = Command::new("git")
-            .current_dir(&self.dir)
-            .args(args)
+        let start = Instant::now();
+        let output = self.command(&args)
             .output()
-            .await?;
+            .await
+            .map_err(|err| self.spawn_error(err))?;
+        self.trace_exit(&args, Some(output.status), start);
 
         if output.status.success() {
             Ok(SuccessOutput {
@@ -44,7 +85,7 @@ impl G {
                 stderr: output.stderr,
             })
         } else {
-            Err(Error::Exit(output))
+            Err(self.exit_error(args, output.status, output.stdout, output.stderr))
         }
     }
 }
@@ -59,7 +100,7 @@ mod tests {
         let output = g.run(vec!["--version".to_string()]).await.unwrap();
         let stdout = String::from_utf8(output.stdout).unwrap();
         println!("{}", stdout);
-        assert!(stdout.len() > 0);
+        assert!(!stdout.is_empty());
     }
 
     #[tokio::test]
@@ -68,15 +109,32 @@ mod tests {
         let output = g.run(vec!["--bad-option".to_string()]).await;
         assert!(output.is_err());
         match output.err().unwrap() {
-            Error::Exit(output) => {
-                let stderr = String::from_utf8(output.stderr).unwrap();
+            Error::Exit(e) => {
+                let stderr = String::from_utf8(e.stderr.clone()).unwrap();
                 println!("{}", stderr);
-                assert!(stderr.len() > 0);
+                assert!(!stderr.is_empty());
+                assert_eq!(e.args, vec!["--bad-option".to_string()]);
+                assert_eq!(e.code(), Some(129));
+
+                let message = e.to_string();
+                assert!(message.starts_with("`git --bad-option` (in "));
+                assert!(message.contains(stderr.trim()));
             }
             _ => panic!("unexpected error type"),
         }
     }
 
+    #[tokio::test]
+    async fn test_run_not_repository() {
+        let dir = env::temp_dir().join("gg-git-test-not-repository");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let g = G::new(&dir);
+        let err = g.run(vec!["rev-parse".to_string(), "--show-toplevel".to_string()]).await.err().unwrap();
+        assert!(err.is_not_repository(), "unexpected error: {}", err);
+        assert_eq!(err.exit_error().unwrap().cwd, dir);
+    }
+
     #[tokio::test]
     async fn test_run_in_not_exist_dir() {
         let g = G::new("/not/exist/dir");
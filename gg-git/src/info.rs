@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+use crate::g::G;
+use crate::result::{Error, Result};
+
+/// layout of the repository a directory belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoInfo {
+    /// root of the working tree, `None` for bare repos or when inside the git dir
+    pub toplevel: Option<PathBuf>,
+    /// the git dir of this checkout (`.git`, `.git/worktrees/<name>` or `.git/modules/<name>`)
+    pub git_dir: PathBuf,
+    /// the git dir shared by all worktrees
+    pub common_dir: PathBuf,
+    pub is_bare: bool,
+    /// root of the superproject's working tree if this is a submodule
+    pub superproject: Option<PathBuf>,
+}
+
+impl RepoInfo {
+    /// whether this is a linked worktree created by `git worktree add`
+    pub fn is_worktree(&self) -> bool {
+        self.git_dir != self.common_dir
+    }
+
+    pub fn is_submodule(&self) -> bool {
+        self.superproject.is_some()
+    }
+
+    fn parse(dir: &Path, stdout: &str) -> Option<RepoInfo> {
+        let mut lines = stdout.lines();
+
+        let is_bare = lines.next()? == "true";
+        let inside_work_tree = lines.next()? == "true";
+        let git_dir = PathBuf::from(lines.next()?);
+        let common_dir = PathBuf::from(lines.next()?);
+
+        // --show-cdup only prints when inside the work tree
+        let toplevel = if inside_work_tree {
+            let cdup = lines.next()?;
+            Some(dir.join(cdup).canonicalize().ok()?)
+        } else {
+            None
+        };
+
+        let superproject = lines.next().filter(|s| !s.is_empty()).map(PathBuf::from);
+
+        Some(RepoInfo {
+            toplevel,
+            git_dir,
+            common_dir,
+            is_bare,
+            superproject,
+        })
+    }
+}
+
+impl G {
+    /// inspect the repository containing the dir
+    ///
+    /// Works for worktrees, submodules, bare repos and `GIT_DIR` setups.
+    pub async fn repo_info(&self) -> Result<RepoInfo> {
+        let args = vec![
+            "rev-parse",
+            "--path-format=absolute",
+            "--is-bare-repository",
+            "--is-inside-work-tree",
+            "--git-dir",
+            "--git-common-dir",
+            "--show-cdup",
+            "--show-superproject-working-tree",
+        ];
+        let args: Vec<String> = args.into_iter().map(String::from).collect();
+
+        let output = self.run(args).await?;
+        let stdout = String::from_utf8(output.stdout)?;
+
+        match RepoInfo::parse(&self.dir, &stdout) {
+            Some(info) => Ok(info),
+            None => Err(Error::UnexpectedOutput(stdout)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::status::tests::{git, setup};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_repo_info_normal() {
+        let repo = setup("info-normal");
+        let nested = repo.join("new");
+
+        let info = G::new(&nested).repo_info().await.unwrap();
+        assert_eq!(info.toplevel, Some(repo.clone()));
+        assert_eq!(info.git_dir, repo.join(".git"));
+        assert!(!info.is_bare);
+        assert!(!info.is_worktree());
+        assert!(!info.is_submodule());
+
+        assert!(G::new(&repo).is_root().await.unwrap());
+        assert!(!G::new(&nested).is_root().await.unwrap());
+        assert!(!G::new(repo.parent().unwrap()).is_root().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_repo_info_worktree() {
+        let repo = setup("info-worktree");
+        git(&repo, &["worktree", "add", "-q", "wt"]);
+        let wt = repo.join("wt");
+
+        let info = G::new(&wt).repo_info().await.unwrap();
+        assert_eq!(info.toplevel, Some(wt.clone()));
+        assert_eq!(info.common_dir, repo.join(".git"));
+        assert!(info.is_worktree());
+        assert!(G::new(&wt).is_root().await.unwrap());
+
+        let work_tree = G::new(&wt).work_tree().await.unwrap();
+        assert_eq!(work_tree.root, wt);
+        assert_eq!(work_tree.git_dir, info.git_dir);
+    }
+
+    #[tokio::test]
+    async fn test_repo_info_submodule() {
+        let sub = setup("info-submodule-sub");
+        let repo = setup("info-submodule");
+        git(&repo, &["-c", "protocol.file.allow=always", "submodule", "add", "-q", sub.to_str().unwrap(), "sub"]);
+
+        let info = G::new(repo.join("sub")).repo_info().await.unwrap();
+        assert_eq!(info.toplevel, Some(repo.join("sub")));
+        assert_eq!(info.superproject, Some(repo.clone()));
+        assert!(info.is_submodule());
+        assert!(!info.is_worktree());
+        assert!(G::new(repo.join("sub")).is_root().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_repo_info_bare() {
+        let dir = setup("info-bare");
+        git(&dir, &["init", "-q", "--bare", "repo.git"]);
+
+        let info = G::new(dir.join("repo.git")).repo_info().await.unwrap();
+        assert!(info.is_bare);
+        assert_eq!(info.toplevel, None);
+        assert_eq!(info.git_dir, dir.join("repo.git"));
+        assert!(!G::new(dir.join("repo.git")).is_root().await.unwrap());
+    }
+}
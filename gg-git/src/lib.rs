@@ -1,6 +1,15 @@
 mod result;
 mod g;
 mod root;
+mod info;
+mod stream;
+mod status;
+mod backend;
 
-pub use g::G;
-pub use root::{get_root, is_root};
\ No newline at end of file
+pub use g::{G, SuccessOutput};
+pub use result::{Error, ExitError, Result};
+pub use root::{get_root, is_root};
+pub use info::RepoInfo;
+pub use stream::{Delimiter, GitStream};
+pub use status::{Change, StatusEntry};
+pub use backend::*;
\ No newline at end of file
@@ -1,13 +1,158 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::process::ExitStatus;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("io error: {0}")]
     IO(#[from] std::io::Error),
-    #[error("command exit with non-zero status: {}", String::from_utf8_lossy(&.0.stdout))]
-    Exit(std::process::Output),
+    #[error("git is not installed or not found in PATH")]
+    GitNotFound,
+    #[error("not a git repository: {}", .cwd.display())]
+    NotRepository {
+        cwd: PathBuf,
+        /// the failed git command, `None` if not reported by the git cli
+        exit: Option<Box<ExitError>>,
+    },
+    #[error("git refuses to work in {} because of dubious ownership, \
+             consider `git config --global --add safe.directory <dir>`", .0.cwd.display())]
+    DubiousOwnership(Box<ExitError>),
+    #[error("another git process seems to be running ({0})")]
+    LockFileExists(Box<ExitError>),
+    #[error("{0}")]
+    Exit(Box<ExitError>),
     #[error("utf8 error: {0}")]
     Utf8(#[from] std::string::FromUtf8Error),
+    #[error("unexpected git output: {}", .0.trim())]
+    UnexpectedOutput(String),
+    #[cfg(feature = "gix")]
+    #[error("gix error: {0}")]
+    Gix(Box<dyn std::error::Error + Send + Sync>),
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+impl Error {
+    /// classify a failed git command by its stderr
+    pub(crate) fn from_exit(e: ExitError) -> Error {
+        let stderr = String::from_utf8_lossy(&e.stderr).to_lowercase();
+        let e = Box::new(e);
+
+        if stderr.contains("not a git repository") {
+            Error::NotRepository {
+                cwd: e.cwd.clone(),
+                exit: Some(e),
+            }
+        } else if stderr.contains("dubious ownership") {
+            Error::DubiousOwnership(e)
+        } else if stderr.contains(".lock': file exists") {
+            Error::LockFileExists(e)
+        } else {
+            Error::Exit(e)
+        }
+    }
+
+    /// the failed git command, if the error is caused by git exiting with non-zero status
+    pub fn exit_error(&self) -> Option<&ExitError> {
+        match self {
+            Error::NotRepository { exit: Some(e), .. }
+            | Error::DubiousOwnership(e)
+            | Error::LockFileExists(e)
+            | Error::Exit(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_repository(&self) -> bool {
+        matches!(self, Error::NotRepository { .. })
+    }
+
+    pub fn is_git_not_found(&self) -> bool {
+        matches!(self, Error::GitNotFound)
+    }
+}
+
+/// details of a git command that exited with non-zero status
+#[derive(Debug, Clone)]
+pub struct ExitError {
+    /// arguments passed to git (without `git` itself)
+    pub args: Vec<String>,
+    /// working directory the command ran in
+    pub cwd: PathBuf,
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl ExitError {
+    /// exit code, `None` if the command was terminated by a signal
+    pub fn code(&self) -> Option<i32> {
+        self.status.code()
+    }
+
+    /// the command line, e.g. `git rev-parse --show-toplevel`
+    pub fn command_line(&self) -> String {
+        let mut s = "git".to_string();
+        for arg in &self.args {
+            s.push(' ');
+            s.push_str(arg);
+        }
+        s
+    }
+}
+
+impl fmt::Display for ExitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` (in {}) ", self.command_line(), self.cwd.display())?;
+        match self.code() {
+            Some(code) => write!(f, "exited with code {}", code)?,
+            None => write!(f, "was terminated by signal")?,
+        }
+
+        let stderr = String::from_utf8_lossy(&self.stderr);
+        let stderr = stderr.trim();
+        if !stderr.is_empty() {
+            write!(f, ": {}", stderr)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit_error(stderr: &str) -> ExitError {
+        let status = std::process::Command::new("git")
+            .arg("--bad-option")
+            .stderr(std::process::Stdio::null())
+            .status()
+            .unwrap();
+
+        ExitError {
+            args: vec!["status".to_string()],
+            cwd: PathBuf::from("/repo"),
+            status,
+            stdout: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let err = Error::from_exit(exit_error("fatal: not a git repository (or any of the parent directories): .git\n"));
+        assert!(err.is_not_repository());
+
+        let err = Error::from_exit(exit_error("fatal: detected dubious ownership in repository at '/repo'\n"));
+        assert!(matches!(err, Error::DubiousOwnership(_)));
+
+        let err = Error::from_exit(exit_error("fatal: Unable to create '/repo/.git/index.lock': File exists.\n"));
+        assert!(matches!(err, Error::LockFileExists(_)));
+
+        let err = Error::from_exit(exit_error("fatal: something else\n"));
+        assert!(matches!(err, Error::Exit(_)));
+        assert_eq!(err.to_string(), "`git status` (in /repo) exited with code 129: fatal: something else");
+    }
+}
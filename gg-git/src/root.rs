@@ -1,6 +1,8 @@
+use std::env;
 use std::path::PathBuf;
+use crate::backend::{backend, Backend, WorkTree};
 use crate::g::G;
-use crate::result::Result;
+use crate::result::{Error, Result};
 
 impl G {
     pub async fn root(&self) -> Result<PathBuf> {
@@ -10,16 +12,41 @@ impl G {
         Ok(PathBuf::from(stdout.trim()))
     }
 
+    pub async fn work_tree(&self) -> Result<WorkTree> {
+        let args = vec!["rev-parse", "--path-format=absolute", "--show-toplevel", "--git-dir"];
+        let output = self.run(args.into_iter().map(String::from).collect()).await?;
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let mut lines = stdout.lines();
+        match (lines.next(), lines.next()) {
+            (Some(root), Some(git_dir)) => Ok(WorkTree {
+                root: PathBuf::from(root),
+                git_dir: PathBuf::from(git_dir),
+            }),
+            _ => Err(Error::UnexpectedOutput(stdout)),
+        }
+    }
+
+    /// check if the dir is the root of a working tree
+    ///
+    /// Returns `Ok(false)` if the dir is not inside a git repository.
     pub async fn is_root(&self) -> Result<bool> {
-        // simply check if there's .git directory
-        let d = self.dir.join(".git");
-        Ok(d.try_exists()?)
+        let info = match self.repo_info().await {
+            Ok(info) => info,
+            Err(err) if err.is_not_repository() => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        match info.toplevel {
+            Some(toplevel) => Ok(toplevel == self.dir.canonicalize()?),
+            None => Ok(false),
+        }
     }
 }
 
+/// get the repo root of current dir with the default backend
 pub async fn get_root() -> Result<PathBuf> {
-    let g = G::default();
-    g.root().await
+    Backend::root(&backend(env::current_dir()?)).await
 }
 
 pub async fn is_root() -> Result<bool> {
@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+use crate::g::G;
+use crate::result::{Error, Result};
+use crate::stream::Delimiter;
+
+/// kind of a change between the index and the working tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Change {
+    Modified,
+    /// added with `git add --intent-to-add`
+    Added,
+    Deleted,
+    TypeChanged,
+    Unmerged,
+    Untracked,
+}
+
+/// a file with changes in the working tree, relative to the repo root
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StatusEntry {
+    pub path: PathBuf,
+    pub change: Change,
+}
+
+impl Change {
+    /// parse the `XY` code of `git status --porcelain`, looking at the working tree side only
+    fn from_porcelain(x: u8, y: u8) -> Option<Change> {
+        match (x, y) {
+            (b'?', b'?') => Some(Change::Untracked),
+            (b'D', b'D') | (b'A', b'A') | (b'U', _) | (_, b'U') => Some(Change::Unmerged),
+            (_, b'M') => Some(Change::Modified),
+            (_, b'A') => Some(Change::Added),
+            (_, b'D') => Some(Change::Deleted),
+            (_, b'T') => Some(Change::TypeChanged),
+            _ => None,
+        }
+    }
+}
+
+impl G {
+    /// list unstaged and untracked files
+    ///
+    /// Untracked directories are expanded to the files inside, ignored files are not listed.
+    pub async fn status(&self) -> Result<Vec<StatusEntry>> {
+        // paths in porcelain format are always relative to the repo root
+        let args = vec![
+            "status",
+            "--porcelain",
+            "-z",
+            "--untracked-files=all",
+            "--no-renames",
+        ];
+        let mut stream = self.stream(args.into_iter().map(String::from).collect(), Delimiter::Nul).await?;
+
+        let mut entries = Vec::new();
+        while let Some(record) = stream.next_record().await? {
+            if record.len() < 4 {
+                return Err(Error::UnexpectedOutput(String::from_utf8_lossy(&record).to_string()));
+            }
+
+            if let Some(change) = Change::from_porcelain(record[0], record[1]) {
+                entries.push(StatusEntry {
+                    path: PathBuf::from(String::from_utf8(record[3..].to_vec())?),
+                    change,
+                });
+            }
+        }
+        stream.wait().await?;
+
+        Ok(entries)
+    }
+
+    /// list files whose content in the index differs from HEAD, relative to the repo root
+    ///
+    /// Deleted files are not listed.
+    pub async fn staged_files(&self) -> Result<Vec<PathBuf>> {
+        let args = vec![
+            "diff",
+            "--cached",
+            "--name-only",
+            "--no-renames",
+            "--diff-filter=d",
+            "-z",
+        ];
+        let mut stream = self.stream(args.into_iter().map(String::from).collect(), Delimiter::Nul).await?;
+
+        let mut files = Vec::new();
+        while let Some(file) = stream.next_string().await? {
+            files.push(PathBuf::from(file));
+        }
+        stream.wait().await?;
+
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::path::Path;
+    use std::process::Command;
+    use super::*;
+
+    pub(crate) fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=gg", "-c", "user.email=gg@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// a repo with one file of every kind of change
+    pub(crate) fn setup(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gg-git-test-status-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        git(&dir, &["init", "-q"]);
+        std::fs::write(dir.join("modified.txt"), "a").unwrap();
+        std::fs::write(dir.join("deleted.txt"), "a").unwrap();
+        std::fs::write(dir.join("staged.txt"), "a").unwrap();
+        std::fs::write(dir.join("removed.txt"), "a").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "init"]);
+
+        std::fs::write(dir.join("modified.txt"), "b").unwrap();
+        std::fs::remove_file(dir.join("deleted.txt")).unwrap();
+        std::fs::write(dir.join("staged.txt"), "b").unwrap();
+        std::fs::create_dir(dir.join("new")).unwrap();
+        std::fs::write(dir.join("new/untracked.txt"), "a").unwrap();
+        std::fs::write(dir.join("added.txt"), "a").unwrap();
+        git(&dir, &["add", "staged.txt", "added.txt"]);
+        git(&dir, &["rm", "-q", "removed.txt"]);
+
+        dir
+    }
+
+    pub(crate) fn expected_status() -> Vec<StatusEntry> {
+        vec![
+            StatusEntry { path: PathBuf::from("deleted.txt"), change: Change::Deleted },
+            StatusEntry { path: PathBuf::from("modified.txt"), change: Change::Modified },
+            StatusEntry { path: PathBuf::from("new/untracked.txt"), change: Change::Untracked },
+        ]
+    }
+
+    pub(crate) fn expected_staged_files() -> Vec<PathBuf> {
+        vec![PathBuf::from("added.txt"), PathBuf::from("staged.txt")]
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let dir = setup("cli");
+
+        let mut status = G::new(dir.join("new")).status().await.unwrap();
+        status.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(status, expected_status());
+    }
+
+    #[tokio::test]
+    async fn test_staged_files() {
+        let dir = setup("cli-staged");
+
+        let mut files = G::new(&dir).staged_files().await.unwrap();
+        files.sort();
+        assert_eq!(files, expected_staged_files());
+    }
+}
@@ -0,0 +1,229 @@
+use std::process::Stdio;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::{Child, ChildStdout};
+use tokio::task::JoinHandle;
+use crate::g::G;
+use crate::result::Result;
+
+/// how the output of a streaming git command is split into records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// split by `\n` (a trailing `\r` is removed too)
+    Line,
+    /// split by `\0`, for commands run with `-z` or `--null`
+    Nul,
+}
+
+impl Delimiter {
+    fn byte(self) -> u8 {
+        match self {
+            Delimiter::Line => b'\n',
+            Delimiter::Nul => b'\0',
+        }
+    }
+}
+
+/// a running git command whose stdout is read record by record
+///
+/// The child process is killed if the stream is dropped before it finishes.
+pub struct GitStream {
+    g: G,
+    args: Vec<String>,
+    child: Child,
+    stdout: Option<BufReader<ChildStdout>>,
+    stderr: Option<JoinHandle<Vec<u8>>>,
+    delimiter: Delimiter,
+    start: Instant,
+}
+
+impl G {
+    /// spawn a git command and return a stream over its stdout
+    ///
+    /// stderr is collected in background and reported if the command fails.
+    pub async fn stream(&self, args: Vec<String>, delimiter: Delimiter) -> Result<GitStream> {
+        let start = Instant::now();
+        let mut child = self.command(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| self.spawn_error(err))?;
+
+        let stdout = child.stdout.take().map(BufReader::new);
+        let stderr = child.stderr.take().map(|mut stderr| {
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let _ = stderr.read_to_end(&mut buf).await;
+                buf
+            })
+        });
+
+        Ok(GitStream {
+            g: self.clone(),
+            args,
+            child,
+            stdout,
+            stderr,
+            delimiter,
+            start,
+        })
+    }
+
+    /// run a git command attached to the current terminal
+    ///
+    /// stdin, stdout and stderr are inherited, so interactive commands (editors, pagers,
+    /// credential prompts, progress output) work as if git was called directly.
+    pub async fn passthrough(&self, args: Vec<String>) -> Result<()> {
+        let start = Instant::now();
+        let status = self.command(&args)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .await
+            .map_err(|err| self.spawn_error(err))?;
+        self.trace_exit(&args, Some(status), start);
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(self.exit_error(args, status, Vec::new(), Vec::new()))
+        }
+    }
+}
+
+impl GitStream {
+    /// read the next record, without the delimiter
+    ///
+    /// Returns `Ok(None)` once stdout is exhausted.
+    pub async fn next_record(&mut self) -> Result<Option<Vec<u8>>> {
+        let stdout = match self.stdout.as_mut() {
+            Some(stdout) => stdout,
+            None => return Ok(None),
+        };
+
+        let delimiter = self.delimiter.byte();
+        let mut buf = Vec::new();
+        if stdout.read_until(delimiter, &mut buf).await? == 0 {
+            return Ok(None);
+        }
+
+        if buf.last() == Some(&delimiter) {
+            buf.pop();
+        }
+        if self.delimiter == Delimiter::Line && buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+
+        Ok(Some(buf))
+    }
+
+    /// like `next_record`, but decode the record as utf-8
+    pub async fn next_string(&mut self) -> Result<Option<String>> {
+        match self.next_record().await? {
+            Some(record) => Ok(Some(String::from_utf8(record)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// discard the rest of the output and wait for the command to exit
+    pub async fn wait(mut self) -> Result<()> {
+        if let Some(mut stdout) = self.stdout.take() {
+            tokio::io::copy(&mut stdout, &mut tokio::io::sink()).await?;
+        }
+
+        let status = self.child.wait().await?;
+        let stderr = self.collect_stderr().await;
+        self.g.trace_exit(&self.args, Some(status), self.start);
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(self.g.exit_error(self.args, status, Vec::new(), stderr))
+        }
+    }
+
+    /// kill the command without waiting for it to finish
+    pub async fn cancel(mut self) -> Result<()> {
+        self.stdout.take();
+        self.child.kill().await?;
+        self.g.trace_exit(&self.args, None, self.start);
+        Ok(())
+    }
+
+    async fn collect_stderr(&mut self) -> Vec<u8> {
+        match self.stderr.take() {
+            Some(handle) => handle.await.unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::Error;
+
+    #[tokio::test]
+    async fn test_stream_lines() {
+        let g = G::default();
+        let mut stream = g.stream(vec!["--version".to_string()], Delimiter::Line).await.unwrap();
+
+        let line = stream.next_string().await.unwrap().unwrap();
+        assert!(line.starts_with("git version"));
+        assert!(!line.ends_with('\n'));
+
+        assert!(stream.next_record().await.unwrap().is_none());
+        stream.wait().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_nul() {
+        let g = G::default();
+        let args = vec!["ls-files".to_string(), "-z".to_string()];
+        let mut stream = g.stream(args, Delimiter::Nul).await.unwrap();
+
+        let mut files = Vec::new();
+        while let Some(file) = stream.next_string().await.unwrap() {
+            assert!(!file.contains('\0'));
+            files.push(file);
+        }
+        stream.wait().await.unwrap();
+
+        assert!(files.iter().any(|f| f == "Cargo.toml"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_fail() {
+        let g = G::default();
+        let mut stream = g.stream(vec!["--bad-option".to_string()], Delimiter::Line).await.unwrap();
+        while stream.next_record().await.unwrap().is_some() {}
+
+        match stream.wait().await.err().unwrap() {
+            Error::Exit(e) => {
+                assert!(!e.stderr.is_empty());
+                assert_eq!(e.args, vec!["--bad-option".to_string()]);
+            }
+            _ => panic!("unexpected error type"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_cancel() {
+        let g = G::default();
+        let mut stream = g.stream(vec!["log".to_string(), "--format=%H".to_string()], Delimiter::Line).await.unwrap();
+
+        let first = stream.next_string().await.unwrap().unwrap();
+        assert_eq!(first.len(), 40);
+        stream.cancel().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_passthrough() {
+        let g = G::default();
+        g.passthrough(vec!["--version".to_string()]).await.unwrap();
+        assert!(g.passthrough(vec!["--bad-option".to_string()]).await.is_err());
+    }
+}
@@ -1,22 +1,58 @@
 pub use console;
 
+// Macros for messages of gg on stderr, see `render` for how they look.
+
 #[macro_export]
 macro_rules! ep_error {
-    ($($t:tt)*) => {{
-        eprintln!("{} {}",
-              gg_tui::console::console::style(" ERROR ").bg(gg_tui::console::console::Color::Red).black(),
-              gg_tui::console::console::style(format!($($t)*)).red(),
-        )
-    }};
+    ($($t:tt)*) => {
+        gg_tui::render::error(&format!($($t)*))
+    };
 }
 
+/// printed unless `--quiet`
 #[macro_export]
 macro_rules! ep_warning {
-    ($($t:tt)*) => {{
-        eprintln!("{} {}",
-              gg_tui::console::console::style(" WARNING ").bg(gg_tui::console::console::Color::Yellow).black(),
-              gg_tui::console::console::style(format!($($t)*)).yellow(),
-        )
-    }};
+    ($($t:tt)*) => {
+        gg_tui::render::warning(&format!($($t)*))
+    };
+}
+
+/// a failure that is not an error of gg, printed unless `--quiet`
+#[macro_export]
+macro_rules! ep_failure {
+    ($($t:tt)*) => {
+        gg_tui::render::failure(&format!($($t)*))
+    };
 }
 
+/// what gg is doing, printed unless `--quiet`
+#[macro_export]
+macro_rules! ep_info {
+    ($($t:tt)*) => {
+        gg_tui::render::info(&format!($($t)*))
+    };
+}
+
+/// something is done, printed unless `--quiet`
+#[macro_export]
+macro_rules! ep_success {
+    ($($t:tt)*) => {
+        gg_tui::render::success(&format!($($t)*))
+    };
+}
+
+/// a command about to run, printed as `> command` unless `--quiet`
+#[macro_export]
+macro_rules! ep_step {
+    ($($t:tt)*) => {
+        gg_tui::render::step(&format!($($t)*))
+    };
+}
+
+/// printed only with `--verbose`
+#[macro_export]
+macro_rules! ep_verbose {
+    ($($t:tt)*) => {
+        gg_tui::render::verbose(&format!($($t)*))
+    };
+}
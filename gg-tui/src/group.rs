@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{is_quiet, terminal, Ci};
+
+/// a collapsible section of CI logs, e.g. around the output of a task
+///
+/// Markers are `::group::` in GitHub Actions, `section_start` in GitLab CI and a plain header
+/// in other CI. Nothing is printed outside CI or with `--quiet`. The group ends when dropped.
+#[must_use = "the group ends when dropped"]
+pub struct Group {
+    ci: Option<Ci>,
+    section: String,
+}
+
+static SECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+impl Group {
+    pub fn start(title: &str) -> Group {
+        let ci = terminal().ci.filter(|_| !is_quiet());
+        let section = section_id(SECTIONS.fetch_add(1, Ordering::Relaxed), title);
+        if let Some(ci) = ci {
+            eprintln!("{}", start_marker(ci, title, &section, now()));
+        }
+        Group { ci, section }
+    }
+}
+
+impl Drop for Group {
+    fn drop(&mut self) {
+        if let Some(end) = self.ci.and_then(|ci| end_marker(ci, &self.section, now())) {
+            eprintln!("{}", end);
+        }
+    }
+}
+
+fn start_marker(ci: Ci, title: &str, section: &str, now: u64) -> String {
+    match ci {
+        Ci::GithubActions => format!("::group::{}", title),
+        Ci::GitlabCi => format!("\x1b[0Ksection_start:{}:{}[collapsed=true]\r\x1b[0K{}", now, section, title),
+        Ci::Other => format!("==> {}", title),
+    }
+}
+
+fn end_marker(ci: Ci, section: &str, now: u64) -> Option<String> {
+    match ci {
+        Ci::GithubActions => Some("::endgroup::".to_string()),
+        Ci::GitlabCi => Some(format!("\x1b[0Ksection_end:{}:{}\r\x1b[0K", now, section)),
+        Ci::Other => None,
+    }
+}
+
+/// GitLab section ids must be unique and only contain letters, digits, `_`, `.` and `-`
+fn section_id(n: usize, title: &str) -> String {
+    let title: String = title.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    format!("gg_{}_{}", n, title)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers() {
+        let section = section_id(3, "build (before)");
+        assert_eq!(section, "gg_3_build__before_");
+
+        assert_eq!(start_marker(Ci::GithubActions, "build", &section, 10), "::group::build");
+        assert_eq!(end_marker(Ci::GithubActions, &section, 12).as_deref(), Some("::endgroup::"));
+
+        assert_eq!(
+            start_marker(Ci::GitlabCi, "build (before)", &section, 10),
+            "\x1b[0Ksection_start:10:gg_3_build__before_[collapsed=true]\r\x1b[0Kbuild (before)",
+        );
+        assert_eq!(end_marker(Ci::GitlabCi, &section, 12).as_deref(), Some("\x1b[0Ksection_end:12:gg_3_build__before_\r\x1b[0K"));
+
+        assert_eq!(start_marker(Ci::Other, "build", &section, 10), "==> build");
+        assert_eq!(end_marker(Ci::Other, &section, 12), None);
+    }
+}
@@ -1 +1,15 @@
 pub mod console;
+mod group;
+pub mod progress;
+pub mod prompt;
+pub mod render;
+mod settings;
+mod table;
+mod terminal;
+mod theme;
+
+pub use group::Group;
+pub use settings::{is_quiet, is_verbose, set_colors, set_verbosity, terminal, verbosity, Verbosity};
+pub use table::Table;
+pub use terminal::{Ci, Renderer, Terminal};
+pub use theme::{theme, Theme};
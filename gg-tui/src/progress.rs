@@ -0,0 +1,46 @@
+use std::borrow::Cow;
+use std::time::Duration;
+use indicatif::{ProgressDrawTarget, ProgressStyle};
+use crate::{is_quiet, terminal, theme};
+
+pub use indicatif::ProgressBar;
+
+/// a spinner on stderr for an operation of unknown length
+///
+/// Like all progress, it is hidden with `--quiet`, in CI or if stderr is not a terminal.
+pub fn spinner(message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    let color = color();
+    let bar = ProgressBar::with_draw_target(None, draw_target())
+        .with_style(ProgressStyle::with_template(&format!("{{spinner{}}} {{msg}}", color)).unwrap())
+        .with_message(message);
+    bar.enable_steady_tick(Duration::from_millis(100));
+    bar
+}
+
+/// a progress bar on stderr for a transfer of `len` bytes, a spinner if `len` is unknown
+pub fn progress_bar(len: Option<u64>, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    let Some(len) = len else {
+        return spinner(message);
+    };
+
+    let template = format!("{{msg}} [{{bar:30{}}}] {{bytes}}/{{total_bytes}} ({{bytes_per_sec}})", color());
+    ProgressBar::with_draw_target(Some(len), draw_target())
+        .with_style(ProgressStyle::with_template(&template).unwrap().progress_chars("=> "))
+        .with_message(message)
+}
+
+fn draw_target() -> ProgressDrawTarget {
+    if is_quiet() || !terminal().interactive {
+        ProgressDrawTarget::hidden()
+    } else {
+        ProgressDrawTarget::stderr()
+    }
+}
+
+/// color of the template from the theme, e.g. `.cyan`, empty if colors are off
+fn color() -> String {
+    match console::colors_enabled_stderr() {
+        true => format!(".{}", theme().progress),
+        false => String::new(),
+    }
+}
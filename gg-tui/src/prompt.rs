@@ -0,0 +1,61 @@
+use std::io::{self, IsTerminal};
+use console::Term;
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use dialoguer::{Confirm, Input, Select};
+use crate::terminal;
+
+/// prompts are shown only if both stdin and stderr are terminals and not in CI, otherwise
+/// defaults are used
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && terminal().interactive
+}
+
+/// ask a yes or no question
+pub fn confirm(prompt: &str, default: bool) -> io::Result<bool> {
+    if !is_interactive() {
+        return Ok(default);
+    }
+    Confirm::with_theme(dialoguer_theme().as_ref())
+        .with_prompt(prompt)
+        .default(default)
+        .interact_on(&Term::stderr())
+        .map_err(into_io)
+}
+
+/// choose one of `items`, return its index
+pub fn select<T: ToString>(prompt: &str, items: &[T], default: usize) -> io::Result<usize> {
+    if !is_interactive() {
+        return Ok(default);
+    }
+    Select::with_theme(dialoguer_theme().as_ref())
+        .with_prompt(prompt)
+        .items(items)
+        .default(default)
+        .interact_on(&Term::stderr())
+        .map_err(into_io)
+}
+
+/// ask for a line of text
+pub fn input(prompt: &str, default: &str) -> io::Result<String> {
+    if !is_interactive() {
+        return Ok(default.to_string());
+    }
+    Input::<String>::with_theme(dialoguer_theme().as_ref())
+        .with_prompt(prompt)
+        .default(default.to_string())
+        .interact_on(&Term::stderr())
+        .map_err(into_io)
+}
+
+fn dialoguer_theme() -> Box<dyn Theme> {
+    match console::colors_enabled_stderr() {
+        true => Box::new(ColorfulTheme::default()),
+        false => Box::new(SimpleTheme),
+    }
+}
+
+fn into_io(err: dialoguer::Error) -> io::Error {
+    match err {
+        dialoguer::Error::IO(err) => err,
+    }
+}
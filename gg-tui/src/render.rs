@@ -0,0 +1,94 @@
+use crate::{is_quiet, is_verbose, terminal, theme, Renderer};
+
+// Messages of gg on stderr, rendered for the terminal gg runs in. Use the `ep_*` macros
+// instead of calling these directly.
+
+pub fn error(message: &str) {
+    match terminal().renderer() {
+        Renderer::Styled => {
+            let theme = theme();
+            eprintln!("{} {}",
+                      theme.error_label.clone().for_stderr().apply_to(" ERROR "),
+                      theme.error.clone().for_stderr().apply_to(message));
+        }
+        Renderer::Plain => eprintln!("error: {}", message),
+        Renderer::GithubActions => eprintln!("::error::{}", escape(message)),
+    }
+}
+
+/// printed unless `--quiet`
+pub fn warning(message: &str) {
+    if is_quiet() {
+        return;
+    }
+    match terminal().renderer() {
+        Renderer::Styled => {
+            let theme = theme();
+            eprintln!("{} {}",
+                      theme.warning_label.clone().for_stderr().apply_to(" WARNING "),
+                      theme.warning.clone().for_stderr().apply_to(message));
+        }
+        Renderer::Plain => eprintln!("warning: {}", message),
+        Renderer::GithubActions => eprintln!("::warning::{}", escape(message)),
+    }
+}
+
+/// a failure that is not an error of gg, e.g. a script exiting with non-zero code,
+/// printed unless `--quiet`
+pub fn failure(message: &str) {
+    if is_quiet() {
+        return;
+    }
+    match terminal().renderer() {
+        Renderer::GithubActions => eprintln!("::error::{}", escape(message)),
+        _ => eprintln!("{}", theme().failure.clone().for_stderr().apply_to(message)),
+    }
+}
+
+// the rest only differ by styles, which are off if colors are
+
+/// printed unless `--quiet`
+pub fn info(message: &str) {
+    if !is_quiet() {
+        eprintln!("{}", theme().info.clone().for_stderr().apply_to(message));
+    }
+}
+
+/// printed unless `--quiet`
+pub fn success(message: &str) {
+    if !is_quiet() {
+        eprintln!("{}", theme().success.clone().for_stderr().apply_to(message));
+    }
+}
+
+/// printed as `> command` unless `--quiet`
+pub fn step(command: &str) {
+    if !is_quiet() {
+        let theme = theme();
+        eprintln!("{} {}",
+                  theme.step_prefix.clone().for_stderr().apply_to(">"),
+                  theme.step.clone().for_stderr().apply_to(command));
+    }
+}
+
+/// printed only with `--verbose`
+pub fn verbose(message: &str) {
+    if is_verbose() {
+        eprintln!("{}", theme().dim.clone().for_stderr().apply_to(message));
+    }
+}
+
+/// escape data of a workflow command, so multi-line messages stay in one annotation
+fn escape(message: &str) -> String {
+    message.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("unknown script x\n100% sure"), "unknown script x%0A100%25 sure");
+    }
+}
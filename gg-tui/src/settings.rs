@@ -0,0 +1,63 @@
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+use crate::terminal::Terminal;
+
+/// how much gg prints besides errors, set once from cli flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// only errors
+    Quiet,
+    Normal,
+    /// also details useful for debugging
+    Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
+
+pub fn is_quiet() -> bool {
+    verbosity() == Verbosity::Quiet
+}
+
+pub fn is_verbose() -> bool {
+    verbosity() == Verbosity::Verbose
+}
+
+static TERMINAL: OnceLock<Terminal> = OnceLock::new();
+
+/// detect the terminal, with colors forced on or off for stdout and stderr unless `None`
+///
+/// Has no effect if the terminal has been detected already.
+pub fn set_colors(enabled: Option<bool>) {
+    TERMINAL.get_or_init(|| {
+        let mut terminal = Terminal::detect();
+        if let Some(enabled) = enabled {
+            terminal.colors = enabled;
+            terminal.colors_stdout = enabled;
+        }
+        init(terminal)
+    });
+}
+
+/// the terminal gg runs in, detected on first use if `set_colors` is not called
+pub fn terminal() -> Terminal {
+    *TERMINAL.get_or_init(|| init(Terminal::detect()))
+}
+
+/// styles of `console` follow the detected colors
+fn init(terminal: Terminal) -> Terminal {
+    console::set_colors_enabled(terminal.colors_stdout);
+    console::set_colors_enabled_stderr(terminal.colors);
+    terminal
+}
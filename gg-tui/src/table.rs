@@ -0,0 +1,121 @@
+use std::fmt;
+use console::measure_text_width;
+use crate::theme;
+
+/// rows with columns aligned by spaces, for listings on stdout
+///
+/// Cells may be styled already, lines have no trailing spaces.
+#[derive(Default)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    stderr: bool,
+}
+
+impl Table {
+    pub fn new() -> Table {
+        Table::default()
+    }
+
+    pub fn header<I, S>(mut self, cells: I) -> Table
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.header = Some(cells.into_iter().map(|cell| cell.to_string()).collect());
+        self
+    }
+
+    /// style the header for stderr instead of stdout
+    pub fn for_stderr(mut self) -> Table {
+        self.stderr = true;
+        self
+    }
+
+    pub fn row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.rows.push(cells.into_iter().map(|cell| cell.to_string()).collect());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths = Vec::new();
+        for row in self.header.iter().chain(&self.rows) {
+            for (i, cell) in row.iter().enumerate() {
+                let width = measure_text_width(cell);
+                match widths.get_mut(i) {
+                    Some(max) if *max < width => *max = width,
+                    Some(_) => {}
+                    None => widths.push(width),
+                }
+            }
+        }
+        widths
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.widths();
+        let write_row = |f: &mut fmt::Formatter<'_>, row: &[String], header: bool| {
+            let mut line = String::new();
+            for (i, cell) in row.iter().enumerate() {
+                if i > 0 {
+                    line.push_str("  ");
+                }
+                // style after measuring, escape codes take no space
+                let cell = match header {
+                    true if self.stderr => theme().header.clone().for_stderr().apply_to(cell).to_string(),
+                    true => theme().header.apply_to(cell).to_string(),
+                    false => cell.clone(),
+                };
+                line.push_str(&cell);
+                if i + 1 < row.len() {
+                    line.push_str(&" ".repeat(widths[i] - measure_text_width(&cell)));
+                }
+            }
+            // an empty last cell would leave the padding of the previous one
+            writeln!(f, "{}", line.trim_end())
+        };
+
+        if let Some(header) = &self.header {
+            write_row(f, header, true)?;
+        }
+        for row in &self.rows {
+            write_row(f, row, false)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        console::set_colors_enabled(false);
+
+        let mut table = Table::new().header(["NAME", "VERSION", "SOURCE"]);
+        table.row(["foo", "1.0.0", "git https://example.com/gg-foo.git"]);
+        table.row(["longer-name", "2", "dir /tmp"]);
+        table.row(["bar", "", ""]);
+        assert_eq!(table.to_string(), "\
+NAME         VERSION  SOURCE
+foo          1.0.0    git https://example.com/gg-foo.git
+longer-name  2        dir /tmp
+bar
+");
+
+        let mut table = Table::new();
+        table.row([console::style("red").red().force_styling(true).to_string(), "x".to_string()]);
+        table.row(["longer".to_string(), "y".to_string()]);
+        assert!(table.to_string().ends_with("red\u{1b}[0m     x\nlonger  y\n"));
+    }
+}
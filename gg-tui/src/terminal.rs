@@ -0,0 +1,122 @@
+use std::env;
+use console::Term;
+
+/// CI services with special support
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ci {
+    GithubActions,
+    GitlabCi,
+    Other,
+}
+
+/// how gg renders its own messages on stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Renderer {
+    /// colored labels, for terminals
+    Styled,
+    /// `error: ...`, for logs and dumb terminals
+    Plain,
+    /// errors and warnings as workflow commands like `::error::...`, shown as annotations
+    GithubActions,
+}
+
+/// what the environment gg runs in supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Terminal {
+    /// colors on stderr
+    pub colors: bool,
+    pub colors_stdout: bool,
+    /// whether spinners, progress bars and prompts can be shown
+    pub interactive: bool,
+    pub ci: Option<Ci>,
+}
+
+impl Terminal {
+    pub fn detect() -> Terminal {
+        Terminal::from_env(|name| env::var(name).ok(), Term::stdout().is_term(), Term::stderr().is_term())
+    }
+
+    /// in order of precedence: `NO_COLOR`, `CLICOLOR_FORCE`, `TERM=dumb`, `CLICOLOR=0`, then
+    /// whether the stream is a terminal or the logs of a CI service which renders colors
+    fn from_env<F: Fn(&str) -> Option<String>>(var: F, stdout_tty: bool, stderr_tty: bool) -> Terminal {
+        let is_set = |name: &str| var(name).is_some_and(|value| !value.is_empty());
+        let is_true = |name: &str| var(name).is_some_and(|value| !matches!(value.as_str(), "" | "0" | "false"));
+
+        let ci = if is_true("GITHUB_ACTIONS") {
+            Some(Ci::GithubActions)
+        } else if is_true("GITLAB_CI") {
+            Some(Ci::GitlabCi)
+        } else if ["CI", "BUILDKITE", "TF_BUILD", "JENKINS_URL", "TEAMCITY_VERSION"].into_iter().any(is_true) {
+            Some(Ci::Other)
+        } else {
+            None
+        };
+        let dumb = var("TERM").as_deref() == Some("dumb");
+
+        let colors = |tty: bool| {
+            if is_set("NO_COLOR") {
+                false
+            } else if is_true("CLICOLOR_FORCE") {
+                true
+            } else if dumb || var("CLICOLOR").as_deref() == Some("0") {
+                false
+            } else {
+                tty || matches!(ci, Some(Ci::GithubActions | Ci::GitlabCi))
+            }
+        };
+
+        Terminal {
+            colors: colors(stderr_tty),
+            colors_stdout: colors(stdout_tty),
+            interactive: stderr_tty && !dumb && ci.is_none(),
+            ci,
+        }
+    }
+
+    pub fn renderer(&self) -> Renderer {
+        match (self.ci, self.colors) {
+            (Some(Ci::GithubActions), _) => Renderer::GithubActions,
+            (_, true) => Renderer::Styled,
+            (_, false) => Renderer::Plain,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terminal(vars: &[(&str, &str)], tty: bool) -> Terminal {
+        let var = |name: &str| vars.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string());
+        Terminal::from_env(var, tty, tty)
+    }
+
+    #[test]
+    fn test_from_env() {
+        let tty = terminal(&[], true);
+        assert!(tty.colors && tty.interactive);
+        assert_eq!(tty.renderer(), Renderer::Styled);
+
+        let piped = terminal(&[], false);
+        assert!(!piped.colors && !piped.interactive);
+        assert_eq!(piped.renderer(), Renderer::Plain);
+
+        assert!(!terminal(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")], true).colors);
+        assert!(terminal(&[("NO_COLOR", ""), ("CLICOLOR_FORCE", "1")], false).colors);
+        assert!(terminal(&[("CLICOLOR_FORCE", "0")], true).colors);
+        assert!(!terminal(&[("CLICOLOR", "0")], true).colors);
+
+        let dumb = terminal(&[("TERM", "dumb")], true);
+        assert!(!dumb.colors && !dumb.interactive);
+
+        let github = terminal(&[("CI", "true"), ("GITHUB_ACTIONS", "true")], false);
+        assert_eq!(github.ci, Some(Ci::GithubActions));
+        assert!(github.colors && !github.interactive);
+        assert_eq!(github.renderer(), Renderer::GithubActions);
+
+        let jenkins = terminal(&[("JENKINS_URL", "https://ci.example.com")], true);
+        assert_eq!(jenkins.ci, Some(Ci::Other));
+        assert!(jenkins.colors && !jenkins.interactive);
+        assert_eq!(terminal(&[("CI", "false")], true).ci, None);
+    }
+}
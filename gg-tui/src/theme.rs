@@ -0,0 +1,55 @@
+use std::sync::OnceLock;
+use console::Style;
+
+/// styles shared by everything gg prints, so all subcommands look the same
+///
+/// Styles target stdout, use `Style::for_stderr` before printing to stderr.
+pub struct Theme {
+    pub error: Style,
+    pub error_label: Style,
+    pub warning: Style,
+    pub warning_label: Style,
+    pub info: Style,
+    /// what the user may do about an error
+    pub hint: Style,
+    pub success: Style,
+    /// a failure that is not an error of gg, e.g. a script exiting with non-zero code
+    pub failure: Style,
+    /// a command being run
+    pub step: Style,
+    /// the `>` before a command being run
+    pub step_prefix: Style,
+    /// details and less important text
+    pub dim: Style,
+    /// headers of tables
+    pub header: Style,
+    /// color of spinners and progress bars, a name understood by indicatif templates
+    pub progress: &'static str,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            error: Style::new().red(),
+            error_label: Style::new().on_red().black(),
+            warning: Style::new().yellow(),
+            warning_label: Style::new().on_yellow().black(),
+            info: Style::new(),
+            hint: Style::new().cyan(),
+            success: Style::new().green(),
+            failure: Style::new().red(),
+            step: Style::new().bold(),
+            step_prefix: Style::new().dim(),
+            dim: Style::new().dim(),
+            header: Style::new().bold().underlined(),
+            progress: "cyan",
+        }
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// the theme of gg
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
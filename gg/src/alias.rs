@@ -0,0 +1,87 @@
+use std::collections::{BTreeMap, HashMap};
+use gg_config::Config;
+
+/// aliases of scripts, from `aliases` of the config and `aliases` of each script
+pub(crate) struct Aliases {
+    aliases: HashMap<String, String>,
+    /// aliases that cannot be used, with the reason
+    conflicts: Vec<String>,
+}
+
+impl Aliases {
+    /// collect aliases of the config, dropping those colliding with builtin subcommands,
+    /// scripts, or each other
+    pub(crate) fn new(config: &Config, builtins: &[String]) -> Aliases {
+        // alias -> scripts claiming it, sorted for stable messages
+        let mut claims: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (alias, script) in &config.aliases {
+            claims.entry(alias).or_default().push(script);
+        }
+        for (script, script_config) in &config.scripts {
+            for alias in &script_config.aliases {
+                claims.entry(alias).or_default().push(script);
+            }
+        }
+
+        let mut aliases = HashMap::new();
+        let mut conflicts = Vec::new();
+        for (alias, mut scripts) in claims {
+            scripts.sort();
+            scripts.dedup();
+
+            if builtins.iter().any(|builtin| builtin == alias) {
+                conflicts.push(format!("alias {} of script {} is shadowed by builtin subcommand {}", alias, scripts.join(", "), alias));
+            } else if config.scripts.contains_key(alias) {
+                conflicts.push(format!("alias {} of script {} is shadowed by script {}", alias, scripts.join(", "), alias));
+            } else if scripts.len() > 1 {
+                conflicts.push(format!("alias {} is ambiguous, it is used by scripts {}", alias, scripts.join(", ")));
+            } else if !config.scripts.contains_key(scripts[0]) {
+                conflicts.push(format!("alias {} refers to unknown script {}", alias, scripts[0]));
+            } else {
+                aliases.insert(alias.to_string(), scripts[0].to_string());
+            }
+        }
+
+        Aliases { aliases, conflicts }
+    }
+
+    /// the script an alias stands for
+    pub(crate) fn get(&self, alias: &str) -> Option<&str> {
+        self.aliases.get(alias).map(|s| s.as_str())
+    }
+
+    pub(crate) fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases() {
+        let config = gg_config::parse(r#"{
+            "aliases": {"t": "test", "r": "run-me", "x": "missing", "b": "bench"},
+            "scripts": {
+                "test": {"command": "cargo test", "aliases": ["t", "run"]},
+                "build": {"command": "cargo build", "aliases": ["b", "test"]},
+                "bench": "cargo bench",
+                "run-me": "echo"
+            }
+        }"#).unwrap();
+
+        let aliases = Aliases::new(&config, &["run".to_string()]);
+        assert_eq!(aliases.get("t"), Some("test"));
+        assert_eq!(aliases.get("r"), Some("run-me"));
+        assert_eq!(aliases.get("b"), None);
+        assert_eq!(aliases.get("run"), None);
+        assert_eq!(aliases.get("x"), None);
+        assert_eq!(aliases.conflicts(), &[
+            "alias b is ambiguous, it is used by scripts bench, build".to_string(),
+            "alias run of script test is shadowed by builtin subcommand run".to_string(),
+            "alias test of script build is shadowed by script test".to_string(),
+            "alias x refers to unknown script missing".to_string(),
+        ]);
+    }
+}
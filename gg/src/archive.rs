@@ -0,0 +1,28 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::result::{Error, Result};
+
+/// whether a file (or url) name looks like a supported archive
+pub(crate) fn is_archive(name: &str) -> bool {
+    name.ends_with(".zip") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// extract the archive into dir, the format is decided by `name` (e.g. `foo.tar.gz`)
+pub(crate) fn extract(archive: &Path, name: &str, dir: &Path) -> Result<()> {
+    let file = fs::File::open(archive)
+        .map_err(|source| Error::Io { action: format!("cannot open {}", archive.display()), source })?;
+
+    let result = if name.ends_with(".zip") {
+        zip::ZipArchive::new(file)
+            .and_then(|mut zip| zip.extract(dir))
+            .map_err(io::Error::from)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        tar::Archive::new(flate2::read::GzDecoder::new(file))
+            .unpack(dir)
+    } else {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "unsupported archive format, expect .tar.gz, .tgz or .zip"))
+    };
+
+    result.map_err(|source| Error::Archive { name: name.to_string(), source })
+}
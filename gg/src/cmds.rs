@@ -1,3 +1,15 @@
 mod run;
+mod ext;
+mod tools;
+mod history;
+mod completions;
+mod scripts;
+mod config;
 
-pub(crate) use run::RunCommand;
\ No newline at end of file
+pub(crate) use run::RunCommand;
+pub(crate) use ext::ExtCommand;
+pub(crate) use tools::ToolsCommand;
+pub(crate) use history::HistoryCommand;
+pub(crate) use completions::{CompleteCommand, CompletionsCommand};
+pub(crate) use scripts::ScriptsCommand;
+pub(crate) use config::ConfigCommand;
\ No newline at end of file
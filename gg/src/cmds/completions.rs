@@ -0,0 +1,165 @@
+use std::io::{self, Write};
+use clap::{Args, ValueEnum};
+use clap_complete::Shell;
+use crate::alias::Aliases;
+use crate::{builtin_subcommands, extension, get_cmd, Context};
+use crate::result::Result;
+
+#[derive(Args)]
+#[command(about = "print shell completion script", long_about = "\
+print shell completion script
+
+Names of scripts, aliases, extensions and tools are completed dynamically in bash, zsh and fish.
+For example, add this to ~/.bashrc:
+
+    source <(gg completions bash)")]
+pub(crate) struct CompletionsCommand {
+    shell: Shell,
+}
+
+/// `gg complete-names <kind>`, called by completion scripts to list names available in current dir
+#[derive(Args)]
+pub(crate) struct CompleteCommand {
+    kind: CompleteKind,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CompleteKind {
+    /// everything that can be used as `gg <name>`
+    Subcommands,
+    /// everything that can be used as `gg run <name>`
+    Scripts,
+}
+
+impl CompletionsCommand {
+    pub(crate) async fn run(&self, _ctx: &Context) -> Result<()> {
+        generate(self.shell, &mut io::stdout());
+        Ok(())
+    }
+}
+
+/// the script generated by clap, followed by the dynamic completion of names if supported
+fn generate(shell: Shell, out: &mut dyn Write) {
+    clap_complete::generate(shell, &mut get_cmd(), "gg", out);
+
+    let dynamic = match shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+        _ => return,
+    };
+    let _ = writeln!(out, "{}", dynamic);
+}
+
+impl CompleteCommand {
+    pub(crate) async fn run(&self, ctx: &Context) -> Result<()> {
+        for name in self.names(ctx) {
+            println!("{}", name);
+        }
+
+        Ok(())
+    }
+
+    /// sorted names of this kind, aliases shadowed by builtins or scripts are left out
+    fn names(&self, ctx: &Context) -> Vec<String> {
+        let config = &ctx.config;
+        let aliases = Aliases::new(config, &builtin_subcommands());
+
+        let mut names: Vec<String> = config.scripts.keys().cloned().collect();
+        names.extend(config.aliases.keys().chain(config.scripts.values().flat_map(|s| &s.aliases))
+            .filter(|alias| aliases.get(alias).is_some())
+            .cloned());
+        if let CompleteKind::Subcommands = self.kind {
+            names.extend(extension::list_available());
+            names.extend(config.tools.keys().cloned());
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+// completion for builtin subcommands is generated by clap, these add names from the config
+
+const BASH: &str = r#"
+_gg_dynamic() {
+    _gg "$@"
+
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local kind=""
+    if [[ ${COMP_CWORD} -eq 1 ]]; then
+        kind=subcommands
+    elif [[ ${COMP_CWORD} -eq 2 && "${COMP_WORDS[1]}" == "run" ]]; then
+        kind=scripts
+    fi
+
+    if [[ -n "${kind}" && "${cur}" != -* ]]; then
+        COMPREPLY+=( $(compgen -W "$(gg complete-names ${kind} 2>/dev/null)" -- "${cur}") )
+    fi
+}
+
+complete -F _gg_dynamic -o bashdefault -o default gg"#;
+
+const ZSH: &str = r#"
+_gg_dynamic() {
+    local -a names
+    if (( CURRENT == 2 )) && [[ "${words[CURRENT]}" != -* ]]; then
+        names=(${(f)"$(gg complete-names subcommands 2>/dev/null)"})
+    elif (( CURRENT == 3 )) && [[ "${words[2]}" == "run" && "${words[CURRENT]}" != -* ]]; then
+        names=(${(f)"$(gg complete-names scripts 2>/dev/null)"})
+    fi
+    (( ${#names} )) && compadd -a names
+
+    _gg "$@"
+}
+
+compdef _gg_dynamic gg"#;
+
+const FISH: &str = r#"
+complete -c gg -n "__fish_use_subcommand" -f -a "(gg complete-names subcommands 2>/dev/null)"
+complete -c gg -n "__fish_seen_subcommand_from run; and test (count (commandline -opc)) -eq 2" -f -a "(gg complete-names scripts 2>/dev/null)""#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        for shell in Shell::value_variants() {
+            let mut buf = Vec::new();
+            clap_complete::generate(*shell, &mut get_cmd(), "gg", &mut buf);
+            assert!(String::from_utf8(buf).unwrap().contains("completions"));
+        }
+
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut buf = Vec::new();
+            generate(shell, &mut buf);
+            let script = String::from_utf8(buf).unwrap();
+            assert!(script.contains("gg complete-names"), "{}", shell);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_complete_names() {
+        let dir = std::env::temp_dir().join("gg-test-complete-names");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".ggrc.json"), r#"{
+            "aliases": {"t": "test", "history": "test", "b": "build"},
+            "scripts": {"test": {"command": "cargo test", "aliases": ["t", "build"]}, "build": "cargo build"},
+            "tools": {"jq": {"version": "1.7", "platforms": {}}}
+        }"#).unwrap();
+        let ctx = Context::new_in(&dir).await;
+
+        let scripts = CompleteCommand { kind: CompleteKind::Scripts }.names(&ctx);
+        assert_eq!(scripts, vec!["b", "build", "t", "test"]);
+
+        // extensions depend on PATH, only check what comes from the config
+        let subcommands = CompleteCommand { kind: CompleteKind::Subcommands }.names(&ctx);
+        for name in ["b", "build", "jq", "t", "test"] {
+            assert!(subcommands.iter().any(|n| n == name), "{} in {:?}", name, subcommands);
+        }
+        assert!(!subcommands.iter().any(|n| n == "history"));
+        assert!(subcommands.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,51 @@
+use clap::{Args, Subcommand};
+use crate::Context;
+use crate::output::{self, ConfigOutput};
+use crate::result::{Error, Result};
+
+#[derive(Args)]
+#[command(about = "inspect the config of the project")]
+pub(crate) struct ConfigCommand {
+    #[command(subcommand)]
+    command: ConfigSubcommand,
+}
+
+#[derive(Subcommand)]
+enum ConfigSubcommand {
+    /// print the loaded config and where it is loaded from
+    Show,
+}
+
+impl ConfigCommand {
+    pub(crate) async fn run(&self, ctx: &Context) -> Result<()> {
+        match &self.command {
+            ConfigSubcommand::Show => {
+                if let Some(err) = &ctx.config_error {
+                    return Err(Error::Config(err.clone()));
+                }
+
+                let config = ConfigOutput {
+                    path: ctx.config_path.as_deref(),
+                    root: ctx.root.as_deref(),
+                    config: &ctx.config,
+                };
+                if output::is_json() {
+                    output::print_json(&config);
+                    return Ok(());
+                }
+
+                match config.path {
+                    Some(path) => println!("config: {}", path.display()),
+                    None => println!("config: none, using defaults"),
+                }
+                match config.root {
+                    Some(root) => println!("root: {}", root.display()),
+                    None => println!("root: none, not in a git repository"),
+                }
+                println!("{}", output::to_pretty_json(config.config));
+            }
+        }
+
+        Ok(())
+    }
+}
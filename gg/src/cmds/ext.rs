@@ -0,0 +1,186 @@
+use clap::{Args, Subcommand};
+use gg_tui::{prompt, Table};
+use crate::Context;
+use crate::extension::{check, install, Registry, RequirementStatus, Source, validate_name};
+use crate::output::{self, ExtensionAction, ExtensionChangeOutput, ExtensionOutput};
+use crate::result::{error, Error, Result};
+
+#[derive(Args)]
+#[command(about = "manage gg extensions")]
+pub(crate) struct ExtCommand {
+    #[command(subcommand)]
+    command: ExtSubcommand,
+}
+
+#[derive(Subcommand)]
+enum ExtSubcommand {
+    /// install an extension from a local directory, a local archive or a git url
+    Install {
+        source: String,
+        /// name of the extension, derived from the source if not set
+        #[arg(long)]
+        name: Option<String>,
+        /// reinstall if already installed
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// list installed extensions
+    List,
+    /// remove installed extensions
+    Remove {
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+    /// reinstall extensions from their sources, all extensions if no name is given
+    Upgrade {
+        names: Vec<String>,
+    },
+    /// install or upgrade extensions declared in the config
+    Sync,
+}
+
+impl ExtCommand {
+    pub(crate) async fn run(&self, ctx: &Context) -> Result<()> {
+        let registry = Registry::default();
+        // what has been done, printed at the end with `--output json`
+        let mut changes = Vec::new();
+        let mut report = |name: &str, action, version: Option<&str>, previous_version: Option<&str>, text: String| {
+            if !output::is_json() {
+                println!("{}", text);
+            }
+            changes.push(ExtensionChangeOutput {
+                name: name.to_string(),
+                action,
+                version: version.map(String::from),
+                previous_version: previous_version.map(String::from),
+            });
+        };
+
+        match &self.command {
+            ExtSubcommand::Install { source, name, force } => {
+                let source = Source::parse(source);
+                let name = name.clone().unwrap_or_else(|| source.default_name());
+
+                if !*force {
+                    if let Some(installed) = registry.get(&name)? {
+                        let message = format!(
+                            "extension {} {} is already installed from {}",
+                            installed.name, installed.version, installed.source,
+                        );
+                        // asked only in a terminal, scripts keep getting the error
+                        let reinstall = !output::is_json() && prompt::confirm(&format!("{}, reinstall?", message), false)
+                            .map_err(|source| Error::Io { action: "cannot read answer".to_string(), source })?;
+                        if !reinstall {
+                            return Err(error(&format!("{}, use --force to reinstall", message)));
+                        }
+                    }
+                }
+
+                let manifest = install(&registry, source, &name).await?;
+                report(&manifest.name, ExtensionAction::Installed, Some(&manifest.version), None,
+                       format!("installed {} {}", manifest.name, manifest.version));
+            }
+            ExtSubcommand::List if output::is_json() => {
+                output::print_json(&registry.list()?.iter().map(ExtensionOutput::from).collect::<Vec<_>>());
+                return Ok(());
+            }
+            ExtSubcommand::List => {
+                let manifests = registry.list()?;
+                if manifests.is_empty() {
+                    println!("no extension installed");
+                    return Ok(());
+                }
+
+                let mut table = Table::new().header(["NAME", "VERSION", "SOURCE"]);
+                for m in manifests {
+                    table.row([m.name, m.version, m.source.to_string()]);
+                }
+                print!("{}", table);
+            }
+            ExtSubcommand::Remove { names } => {
+                for name in names {
+                    let manifest = registry.get(name)?
+                        .ok_or_else(|| not_installed(name))?;
+                    registry.remove(&manifest)?;
+                    report(name, ExtensionAction::Removed, None, None, format!("removed {}", name));
+                }
+            }
+            ExtSubcommand::Upgrade { names } => {
+                let manifests = if names.is_empty() {
+                    registry.list()?
+                } else {
+                    names.iter()
+                        .map(|name| registry.get(name)?.ok_or_else(|| not_installed(name)))
+                        .collect::<Result<Vec<_>>>()?
+                };
+
+                for old in manifests {
+                    let new = install(&registry, old.source, &old.name).await?;
+                    if new.version == old.version {
+                        report(&new.name, ExtensionAction::UpToDate, Some(&new.version), None,
+                               format!("{} {} is up to date", new.name, new.version));
+                    } else {
+                        report(&new.name, ExtensionAction::Upgraded, Some(&new.version), Some(&old.version),
+                               format!("upgraded {} {} -> {}", new.name, old.version, new.version));
+                    }
+                }
+            }
+            ExtSubcommand::Sync => {
+                let mut names: Vec<&String> = ctx.config.extensions.keys().collect();
+                names.sort();
+                // names come from the config of the repo, check them all before installing anything
+                for name in &names {
+                    validate_name(name)?;
+                }
+
+                for name in names {
+                    let requirement = &ctx.config.extensions[name];
+                    if check(&registry, name, requirement)? == RequirementStatus::Satisfied {
+                        let version = registry.get(name)?.map(|manifest| manifest.version);
+                        report(name, ExtensionAction::UpToDate, version.as_deref(), None, format!("{} is up to date", name));
+                        continue;
+                    }
+
+                    let source = requirement.source.as_ref()
+                        .ok_or_else(|| Error::NotDeclared {
+                            what: format!("source of extension {}", name),
+                            hint: Some(format!("set extensions.{}.source, or install it with `gg ext install <source> --name {}`", name, name)),
+                        })?;
+                    // relative paths are relative to the project root
+                    let path = ctx.work_dir().join(source);
+                    let source = if path.exists() {
+                        Source::parse(&path.to_string_lossy())
+                    } else {
+                        Source::parse(source)
+                    };
+
+                    let manifest = install(&registry, source, name).await?;
+                    if check(&registry, name, requirement)? != RequirementStatus::Satisfied {
+                        return Err(Error::VersionMismatch {
+                            what: format!("extension {}", name),
+                            installed: manifest.version,
+                            required: requirement.version.clone().unwrap_or_else(|| "*".to_string()),
+                            hint: Some(format!("update the requirement in extensions.{}, or its source to provide a matching version", name)),
+                        });
+                    }
+                    report(&manifest.name, ExtensionAction::Installed, Some(&manifest.version), None,
+                           format!("installed {} {}", manifest.name, manifest.version));
+                }
+            }
+        }
+
+        if output::is_json() {
+            output::print_json(&changes);
+        }
+
+        Ok(())
+    }
+}
+
+/// an extension missing from the registry, extensions on PATH are not managed by gg
+fn not_installed(name: &str) -> Error {
+    Error::NotInstalled {
+        what: format!("extension {}", name),
+        hint: Some("`gg ext list` shows installed extensions".to_string()),
+    }
+}
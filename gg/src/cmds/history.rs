@@ -0,0 +1,55 @@
+use std::time::Duration;
+use clap::Args;
+use gg_tui::Table;
+use crate::Context;
+use crate::history::{format_age, format_duration, History};
+use crate::output::{self, HistoryOutput};
+use crate::result::{error, Result};
+
+#[derive(Args)]
+#[command(about = "list recent runs of scripts")]
+pub(crate) struct HistoryCommand {
+    /// number of runs to show
+    #[arg(short = 'n', long, default_value_t = 20)]
+    limit: usize,
+}
+
+impl HistoryCommand {
+    pub(crate) async fn run(&self, ctx: &Context) -> Result<()> {
+        let git_dir = ctx.git_dir.as_ref().ok_or_else(|| error("run history is only available in a git repository"))?;
+        let runs = History::for_git_dir(git_dir).list()?;
+        let runs = &runs[runs.len().saturating_sub(self.limit)..];
+
+        if output::is_json() {
+            output::print_json(&runs.iter().map(HistoryOutput::from).collect::<Vec<_>>());
+            return Ok(());
+        }
+
+        if runs.is_empty() {
+            println!("no script has been run yet");
+            return Ok(());
+        }
+
+        let mut table = Table::new().header(["AGE", "CODE", "DURATION", "HEAD", "COMMAND"]);
+        for run in runs {
+            let command = std::iter::once(run.script.as_str())
+                .chain(run.args.iter().map(|s| s.as_str()));
+            let head = run.head.as_deref().map(|head| &head[..head.len().min(7)]).unwrap_or("-");
+            let code = match run.exit_code {
+                0 => run.exit_code.to_string(),
+                code => gg_tui::theme().failure.apply_to(code).to_string(),
+            };
+
+            table.row([
+                format_age(run.started_at),
+                code,
+                format_duration(Duration::from_millis(run.duration_ms)),
+                head.to_string(),
+                shlex::try_join(command).unwrap_or_default(),
+            ]);
+        }
+        print!("{}", table);
+
+        Ok(())
+    }
+}
@@ -1,65 +1,333 @@
-use std::env;
-use std::ops::Add;
-use shlex;
+mod plan;
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use clap::Args;
-use cargo_util::ProcessBuilder;
-use gg_config::OptionalLoadedConfig;
-use crate::result::{error, Result};
-use gg_tui::{ep_warning};
+use tokio::process::Command;
+use crate::{builtin_subcommands, Context};
+use crate::alias::Aliases;
+use crate::history::{self, format_duration, History, Run};
+use crate::paths::prepend_path;
+use crate::{output, process, suggest, tool};
+use crate::output::{PlanOutput, RunOutput};
+use plan::{Plan, Step, StepKind};
+use crate::result::{error, Error, Result};
+use gg_tui::{ep_failure, ep_step, ep_success, ep_warning, Group, Table};
+use tracing::debug;
 
 #[derive(Args)]
 #[command(about = "run a pre-defined script")]
 pub(crate) struct RunCommand {
+    /// repeat the last run listed by `gg history`
+    #[arg(long, conflicts_with = "script_and_args")]
+    last: bool,
+    /// print the resolved commands, cwd and PATH without running anything
+    #[arg(long)]
+    dry_run: bool,
+    /// like --dry-run, but also show which config key each value comes from
+    #[arg(long)]
+    explain: bool,
     #[arg(trailing_var_arg = true)]
     script_and_args: Vec<String>,
 }
 
 impl RunCommand {
-    pub(crate) async fn run(&self) -> Result<()> {
-        if self.script_and_args.is_empty() {
-            return Err(error("no script specified"));
+    pub(crate) async fn run(&self, ctx: &Context) -> Result<()> {
+        if let Some(err) = &ctx.config_error {
+            return Err(Error::Config(err.clone()));
         }
+        let config = &ctx.config;
 
-        let config = gg_config::auto_load_for_repo(env::current_dir().unwrap()).await;
-        let config = match config {
-            Ok(config) => config.get(),
-            Err(err) => {
-                return Err(error(&format!("failed to load config: {}", err)));
+        let history = ctx.git_dir.as_deref().map(History::for_git_dir);
+
+        let (script, args) = if self.last {
+            let last = match &history {
+                Some(history) => history.last()?,
+                None => return Err(error("run history is only available in a git repository")),
+            };
+            let last = last.ok_or_else(|| error("no script has been run yet"))?;
+            (last.script, last.args)
+        } else {
+            match self.script_and_args.split_first() {
+                Some((script, args)) => (script.clone(), args.to_vec()),
+                None => return Err(error("no script specified")),
             }
         };
 
-        let script = self.script_and_args[0].to_string();
-        let args: Vec<&str> = self.script_and_args[1..].iter().map(|s| s.as_str()).collect();
+        let script = match config.scripts.contains_key(&script) {
+            true => script,
+            false => match Aliases::new(config, &builtin_subcommands()).get(&script) {
+                Some(aliased) => aliased.to_string(),
+                None => return Err(suggest::unknown_script(&script, ctx)),
+            },
+        };
+        let script_config = &config.scripts[&script];
 
-        let script_config = match config.scripts.get(&script) {
-            Some(c) => c,
-            None => {
-                return Err(error(&format!("script '{}' not found", script)));
+        let plan = Plan::new(ctx, &script, script_config, &args)?;
+        if self.dry_run || self.explain {
+            match output::is_json() {
+                // origins are always included in json
+                true => output::print_json(&PlanOutput::from(&plan)),
+                false => plan.print(self.explain),
             }
+            return Ok(());
+        }
+
+        if ctx.root.is_none() {
+            ep_warning!("not in a git repository, run script in current directory");
+        }
+
+        let head = match (&history, &ctx.root) {
+            (Some(_), Some(root)) => history::head(root).await,
+            _ => None,
         };
+        let started_at = history::now();
+        let start = Instant::now();
 
-        let root = match gg_git::get_root().await {
-            Ok(root) => root,
-            Err(_) => {
-                ep_warning!("failed to get git root, run script in current directory");
-                env::current_dir().unwrap()
-            }
+        let result = run_plan(ctx, &plan).await;
+        let code = match &result {
+            Ok(code) => *code,
+            // a step that cannot be started is a failed run too, the error is returned at last
+            Err(err @ Error::Spawn { .. }) => err.exit_code(),
+            Err(_) => return result.map(|_| ()),
         };
 
-        let joined_args = shlex::try_join(args).unwrap();
-        let command_and_args = script_config.command.to_string().add(" ").add(&joined_args);
+        let elapsed = start.elapsed();
+        if output::is_json() {
+            output::print_json(&RunOutput {
+                script: &script,
+                args: &args,
+                exit_code: code,
+                duration_ms: elapsed.as_millis() as u64,
+            });
+        } else {
+            let duration = format_duration(elapsed);
+            match code {
+                0 => ep_success!("{} finished in {}", script, duration),
+                code => ep_failure!("{} failed with exit code {} after {}", script, code, duration),
+            }
+        }
+
+        if let Some(history) = &history {
+            let run = Run {
+                script,
+                args,
+                cwd: ctx.cwd.clone(),
+                started_at,
+                duration_ms: elapsed.as_millis() as u64,
+                exit_code: code,
+                head,
+            };
+            if let Err(err) = history.append(&run) {
+                ep_warning!("cannot record run history: {}", err);
+            }
+        }
+
+        match result? {
+            0 => Ok(()),
+            code => Err(Error::Exit(code)),
+        }
+    }
+}
+
+/// run the steps of the plan and return the exit code of the script
+async fn run_plan(ctx: &Context, plan: &Plan) -> Result<i32> {
+    if !ctx.config.tools.is_empty() {
+        tool::install_all(ctx).await?;
+    }
+
+    let path: Vec<_> = plan.path.iter().map(|dir| dir.value.clone()).collect();
+    let path = (!path.is_empty()).then(|| prepend_path(&path));
 
-        println!("> {}", command_and_args);
+    let tasks = Mutex::new(Vec::new());
+    let result = run_steps(plan, path.as_ref(), &tasks).await;
+    if gg_tui::terminal().ci.is_some() && !gg_tui::is_quiet() {
+        print_summary(plan, &tasks.into_inner().unwrap());
+    }
+    result
+}
 
-        let args = shlex::split(&command_and_args).unwrap();
-        let program = args[0].clone();
-        let args = &args[1..];
+/// a step that has been run, `code` is `None` if it failed to start
+struct Task {
+    kind: StepKind,
+    code: Option<i32>,
+    duration: Duration,
+}
+
+/// run `before`, the command and the hooks in order, return the exit code of the script
+///
+/// If `before` or the command cannot be started, the script fails with the exit code of
+/// the error, hooks still run, and the error is returned at last.
+async fn run_steps(plan: &Plan, path: Option<&OsString>, tasks: &Mutex<Vec<Task>>) -> Result<i32> {
+    let run = |step| run_step(step, &plan.script, &plan.cwd.value, path, tasks);
+
+    let mut code = 0;
+    let mut spawn_error = None;
+    for step in plan.before.iter().chain([&plan.command]) {
+        code = match run(step).await {
+            Ok(code) => code,
+            Err(err) => {
+                let code = err.exit_code();
+                spawn_error = Some(err);
+                code
+            }
+        };
+        if code != 0 {
+            break;
+        }
+    }
 
-        let err = ProcessBuilder::new(program).args(args).cwd(root).exec_replace().err();
-        if err.is_some() {
-            return Err(error(&format!("failed to execute command: {}", err.unwrap())));
+    if code != 0 {
+        if let Some(on_failure) = &plan.on_failure {
+            // the script's exit code is reported no matter what happens here
+            match run(on_failure).await {
+                Ok(0) => {}
+                Ok(hook_code) => ep_warning!("on_failure command exited with code {}", hook_code),
+                Err(err) => ep_warning!("{}", err),
+            }
         }
+    }
 
-        return Ok(());
+    if let Some(after) = &plan.after {
+        if code == 0 {
+            code = run(after).await?;
+        } else if plan.always {
+            if let Err(err) = run(after).await {
+                ep_warning!("{}", err);
+            }
+        }
     }
-}
\ No newline at end of file
+
+    match spawn_error {
+        Some(err) => Err(err),
+        None => Ok(code),
+    }
+}
+
+/// task, status and duration of each step, so the result is easy to find at the end of CI logs
+fn print_summary(plan: &Plan, tasks: &[Task]) {
+    let theme = gg_tui::theme();
+    let mut table = Table::new().header(["TASK", "STATUS", "DURATION"]).for_stderr();
+    for step in plan.steps() {
+        let task = tasks.iter().find(|task| task.kind == step.kind);
+        let (status, duration) = match task {
+            Some(Task { code: Some(0), duration, .. }) => (theme.success.apply_to("ok".to_string()), format_duration(*duration)),
+            Some(Task { code: Some(code), duration, .. }) => (theme.failure.apply_to(format!("failed ({})", code)), format_duration(*duration)),
+            Some(Task { code: None, .. }) => (theme.failure.apply_to("not started".to_string()), "-".to_string()),
+            None => (theme.dim.apply_to("skipped".to_string()), "-".to_string()),
+        };
+        table.row([task_name(&plan.script, step.kind), status.for_stderr().to_string(), duration]);
+    }
+    eprint!("{}", table);
+}
+
+/// `build` for the command of script build, `build (before)` for its hooks
+fn task_name(script: &str, kind: StepKind) -> String {
+    match kind {
+        StepKind::Command => script.to_string(),
+        kind => format!("{} ({})", script, kind),
+    }
+}
+
+/// run a step of the script in a group of CI logs and return its exit code
+async fn run_step(step: &Step, script: &str, cwd: &Path, path: Option<&OsString>, tasks: &Mutex<Vec<Task>>) -> Result<i32> {
+    let _group = Group::start(&task_name(script, step.kind));
+    ep_step!("{}", step.command_line);
+
+    let argv = &step.argv.value;
+    debug!(cwd = %cwd.display(), "running {} {:?} from {}", step.kind, argv, step.argv.origin);
+
+    let start = Instant::now();
+    let mut command = Command::new(&argv[0]);
+    command.args(&argv[1..]).current_dir(cwd);
+    if let Some(path) = path {
+        command.env("PATH", path);
+    }
+    if output::is_json() {
+        // keep stdout for the result of gg
+        command.stdout(std::io::stderr());
+    }
+
+    let code = process::supervise(command).await.map(process::exit_code);
+    tasks.lock().unwrap().push(Task { kind: step.kind, code: code.as_ref().ok().copied(), duration: start.elapsed() });
+
+    let code = code.map_err(|source| Error::Spawn { program: argv[0].clone(), source })?;
+    debug!(code, elapsed = ?start.elapsed(), "{} finished", step.kind);
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// run script `test` declared by `script` and return its exit code and the steps run, in order
+    async fn run_script(name: &str, script: &str) -> (i32, Vec<StepKind>) {
+        let (result, steps) = try_run_script(name, script).await;
+        (result.unwrap(), steps)
+    }
+
+    async fn try_run_script(name: &str, script: &str) -> (Result<i32>, Vec<StepKind>) {
+        let dir = std::env::temp_dir().join(format!("gg-test-run-steps-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".ggrc.json"), format!(r#"{{"scripts": {{"test": {}}}}}"#, script)).unwrap();
+
+        let ctx = Context::new_in(&dir).await;
+        let plan = Plan::new(&ctx, "test", &ctx.config.scripts["test"], &[]).unwrap();
+        let tasks = Mutex::new(Vec::new());
+        let result = run_steps(&plan, None, &tasks).await;
+        (result, tasks.into_inner().unwrap().into_iter().map(|task| task.kind).collect())
+    }
+
+    #[tokio::test]
+    async fn test_run_steps() {
+        let (code, steps) = run_script("ok", r#"{"command": "true", "before": "true", "after": "true", "on_failure": "true"}"#).await;
+        assert_eq!(code, 0);
+        assert_eq!(steps, [StepKind::Before, StepKind::Command, StepKind::After]);
+
+        // a failing before skips the script, after runs only with always
+        let (code, steps) = run_script("before", r#"{"command": "true", "before": "sh -c 'exit 2'", "after": "true"}"#).await;
+        assert_eq!(code, 2);
+        assert_eq!(steps, [StepKind::Before]);
+
+        let (code, steps) = run_script("always", r#"{"command": "true", "before": "sh -c 'exit 2'", "after": "true", "always": true}"#).await;
+        assert_eq!(code, 2);
+        assert_eq!(steps, [StepKind::Before, StepKind::After]);
+    }
+
+    #[tokio::test]
+    async fn test_run_steps_failure() {
+        // the exit code of the script is kept when on_failure and after fail too
+        let script = r#"{"command": "sh -c 'exit 3'", "on_failure": "sh -c 'exit 4'", "after": "sh -c 'exit 5'", "always": true}"#;
+        let (code, steps) = run_script("failure", script).await;
+        assert_eq!(code, 3);
+        assert_eq!(steps, [StepKind::Command, StepKind::OnFailure, StepKind::After]);
+
+        // hooks that cannot even start don't hide the exit code either
+        let script = r#"{"command": "sh -c 'exit 3'", "on_failure": "gg-test-no-such-command", "after": "gg-test-no-such-command", "always": true}"#;
+        let (code, steps) = run_script("failure-spawn", script).await;
+        assert_eq!(code, 3);
+        assert_eq!(steps, [StepKind::Command, StepKind::OnFailure, StepKind::After]);
+
+        // a command that cannot be started fails the script, hooks still run
+        let script = r#"{"command": "gg-test-no-such-command", "on_failure": "true", "after": "true", "always": true}"#;
+        let (result, steps) = try_run_script("spawn", script).await;
+        let err = result.err().unwrap();
+        assert!(matches!(err, Error::Spawn { .. }));
+        assert_eq!(err.exit_code(), 127);
+        assert_eq!(steps, [StepKind::Command, StepKind::OnFailure, StepKind::After]);
+
+        let script = r#"{"command": "true", "before": "gg-test-no-such-command", "on_failure": "true"}"#;
+        let (result, steps) = try_run_script("spawn-before", script).await;
+        assert_eq!(result.err().unwrap().exit_code(), 127);
+        assert_eq!(steps, [StepKind::Before, StepKind::OnFailure]);
+
+        // without always, after is skipped
+        let (code, steps) = run_script("failure-after", r#"{"command": "sh -c 'exit 3'", "after": "true"}"#).await;
+        assert_eq!(code, 3);
+        assert_eq!(steps, [StepKind::Command]);
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use gg_config::Script;
+use crate::Context;
+use crate::output::{PlanOutput, PlannedToolOutput, SourcedOutput, StepOutput};
+use crate::paths::resolve_path_entries;
+use crate::result::{Error, Result};
+use crate::tool::{is_available, resolve, ToolStore};
+
+/// a value and the config key it comes from, shown by `--explain`
+pub(crate) struct Sourced<T> {
+    pub(crate) value: T,
+    pub(crate) origin: String,
+}
+
+impl<T> Sourced<T> {
+    fn new(value: T, origin: String) -> Sourced<T> {
+        Sourced { value, origin }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StepKind {
+    Before,
+    Command,
+    OnFailure,
+    After,
+}
+
+impl fmt::Display for StepKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StepKind::Before => "before",
+            StepKind::Command => "command",
+            StepKind::OnFailure => "on_failure",
+            StepKind::After => "after",
+        })
+    }
+}
+
+/// a command to run as part of the script
+pub(crate) struct Step {
+    pub(crate) kind: StepKind,
+    /// the command line as written in config, plus arguments from cli for the main command
+    pub(crate) command_line: String,
+    pub(crate) argv: Sourced<Vec<String>>,
+}
+
+/// a pinned tool the script needs
+pub(crate) struct PlannedTool {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) url: String,
+    pub(crate) installed: bool,
+}
+
+/// everything resolved to run a script, without running anything
+pub(crate) struct Plan {
+    pub(crate) script: String,
+    pub(crate) config_path: Option<PathBuf>,
+    pub(crate) cwd: Sourced<PathBuf>,
+    /// dirs prepended to PATH, in order
+    pub(crate) path: Vec<Sourced<PathBuf>>,
+    pub(crate) tools: Vec<PlannedTool>,
+    pub(crate) before: Option<Step>,
+    pub(crate) command: Step,
+    pub(crate) on_failure: Option<Step>,
+    pub(crate) after: Option<Step>,
+    /// run `after` even if the script fails
+    pub(crate) always: bool,
+}
+
+impl Plan {
+    pub(crate) fn new(ctx: &Context, script: &str, script_config: &Script, args: &[String]) -> Result<Plan> {
+        let key = format!("scripts.{}", script);
+
+        let cwd = match &ctx.root {
+            Some(root) => Sourced::new(root.clone(), "repo root".to_string()),
+            None => Sourced::new(ctx.cwd.clone(), "current directory, not in a git repository".to_string()),
+        };
+
+        // like `npm run`, make project-local executables and pinned tools available to scripts
+        let mut path = Vec::new();
+        path.extend(resolve_path(&cwd.value, &script_config.path, &format!("{}.path", key))?);
+        path.extend(resolve_path(&cwd.value, &ctx.config.path, "path")?);
+
+        let store = ToolStore::default();
+        let mut names: Vec<&String> = ctx.config.tools.keys().collect();
+        names.sort();
+        let mut tools = Vec::with_capacity(names.len());
+        // tools not available for this platform are skipped by `tool::install_all`
+        for name in names.into_iter().filter(|name| is_available(&ctx.config.tools[*name])) {
+            let tool = resolve(name, &ctx.config.tools[name])?;
+            let binary = store.binary(&tool);
+            path.push(Sourced::new(binary.parent().unwrap().to_path_buf(), format!("tools.{}", name)));
+            tools.push(PlannedTool {
+                name: name.clone(),
+                version: tool.version.to_string(),
+                url: tool.url.clone(),
+                installed: binary.is_file(),
+            });
+        }
+
+        let mut command_line = script_config.command.clone();
+        let mut command_origin = key.clone();
+        if !args.is_empty() {
+            command_line.push(' ');
+            command_line.push_str(&shlex::try_join(args.iter().map(|s| s.as_str())).unwrap());
+            command_origin.push_str(", with arguments from command line");
+        }
+
+        let hook = |kind: StepKind, command_line: &Option<String>| {
+            command_line.as_ref()
+                .map(|command_line| Step::new(kind, command_line.clone(), format!("{}.{}", key, kind)))
+                .transpose()
+        };
+
+        Ok(Plan {
+            script: script.to_string(),
+            config_path: ctx.config_path.clone(),
+            cwd,
+            path,
+            tools,
+            before: hook(StepKind::Before, &script_config.before)?,
+            command: Step::new(StepKind::Command, command_line, command_origin)?,
+            on_failure: hook(StepKind::OnFailure, &script_config.on_failure)?,
+            after: hook(StepKind::After, &script_config.after)?,
+            always: script_config.always,
+        })
+    }
+
+    /// print the plan, with where each value comes from if `explain`
+    pub(crate) fn print(&self, explain: bool) {
+        let origin = |origin: &str| {
+            if explain {
+                format!("  # {}", origin)
+            } else {
+                String::new()
+            }
+        };
+
+        println!("script: {}", self.script);
+        match &self.config_path {
+            Some(path) => println!("config: {}", path.display()),
+            None => println!("config: none"),
+        }
+        println!("cwd: {}{}", self.cwd.value.display(), origin(&self.cwd.origin));
+
+        if !self.tools.is_empty() {
+            println!("tools:");
+            for tool in &self.tools {
+                let state = if tool.installed { "installed" } else { "will be installed" };
+                println!("  {} {} ({}){}", tool.name, tool.version, state, origin(&format!("tools.{} from {}", tool.name, tool.url)));
+            }
+        }
+
+        if !self.path.is_empty() {
+            println!("PATH prepends:");
+            for dir in &self.path {
+                println!("  {}{}", dir.value.display(), origin(&dir.origin));
+            }
+        }
+
+        for step in self.steps() {
+            let condition = match step.kind {
+                StepKind::OnFailure => " (if failed)",
+                StepKind::After if self.always => " (always)",
+                StepKind::After => " (if succeeded)",
+                _ => "",
+            };
+            println!("{}{}: {:?}{}", step.kind, condition, step.argv.value, origin(&step.argv.origin));
+        }
+    }
+
+    pub(crate) fn steps(&self) -> impl Iterator<Item = &Step> {
+        self.before.iter()
+            .chain(Some(&self.command))
+            .chain(self.on_failure.iter())
+            .chain(self.after.iter())
+    }
+}
+
+impl Step {
+    fn new(kind: StepKind, command_line: String, origin: String) -> Result<Step> {
+        let argv = shlex::split(&command_line)
+            .filter(|args| !args.is_empty())
+            .ok_or_else(|| Error::InvalidConfig {
+                message: format!("invalid command in {}: {}", origin, command_line),
+                hint: Some("quotes must be closed, and the command must not be empty".to_string()),
+            })?;
+
+        Ok(Step {
+            kind,
+            command_line,
+            argv: Sourced::new(argv, origin),
+        })
+    }
+}
+
+/// resolve `path` entries of config one by one to keep track of their origins
+fn resolve_path(root: &Path, entries: &[String], key: &str) -> Result<Vec<Sourced<PathBuf>>> {
+    let mut dirs = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let origin = match entry.starts_with('@') {
+            true => format!("{}[{}] ({})", key, i, entry),
+            false => format!("{}[{}]", key, i),
+        };
+        for dir in resolve_path_entries(root, std::slice::from_ref(entry))? {
+            dirs.push(Sourced::new(dir, origin.clone()));
+        }
+    }
+    Ok(dirs)
+}
+
+impl<'a> From<&'a Plan> for PlanOutput<'a> {
+    fn from(plan: &'a Plan) -> Self {
+        let dir = |dir: &'a Sourced<PathBuf>| SourcedOutput { value: dir.value.as_path(), origin: &dir.origin };
+        let step = |step: &'a Step| StepOutput {
+            kind: step.kind.to_string(),
+            command_line: &step.command_line,
+            argv: SourcedOutput { value: &step.argv.value, origin: &step.argv.origin },
+        };
+        PlanOutput {
+            script: &plan.script,
+            config_path: plan.config_path.as_deref(),
+            cwd: dir(&plan.cwd),
+            path: plan.path.iter().map(dir).collect(),
+            tools: plan.tools.iter()
+                .map(|tool| PlannedToolOutput { name: &tool.name, version: &tool.version, url: &tool.url, installed: tool.installed })
+                .collect(),
+            before: plan.before.as_ref().map(step),
+            command: step(&plan.command),
+            on_failure: plan.on_failure.as_ref().map(step),
+            after: plan.after.as_ref().map(step),
+            always: plan.always,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_plan() {
+        let dir = std::env::temp_dir().join("gg-test-run-plan");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        std::fs::write(dir.join(".ggrc.json"), r#"{"path": ["bin"], "scripts": {
+            "build": {"command": "make all", "before": "make clean", "path": ["@node"], "always": true}
+        }}"#).unwrap();
+
+        let ctx = Context::new_in(&dir).await;
+        let script = &ctx.config.scripts["build"];
+        let plan = Plan::new(&ctx, "build", script, &["-j".to_string(), "a b".to_string()]).unwrap();
+
+        assert_eq!(plan.cwd.value, dir);
+        assert_eq!(plan.config_path, Some(dir.join(".ggrc.json")));
+
+        let path: Vec<_> = plan.path.iter().map(|dir| (dir.value.clone(), dir.origin.as_str())).collect();
+        assert_eq!(path, vec![
+            (dir.join("node_modules/.bin"), "scripts.build.path[0] (@node)"),
+            (dir.join("bin"), "path[0]"),
+        ]);
+
+        let before = plan.before.as_ref().unwrap();
+        assert_eq!(before.argv.value, vec!["make", "clean"]);
+        assert_eq!(before.argv.origin, "scripts.build.before");
+
+        assert_eq!(plan.command.command_line, "make all -j 'a b'");
+        assert_eq!(plan.command.argv.value, vec!["make", "all", "-j", "a b"]);
+        assert!(plan.after.is_none());
+        assert!(plan.always);
+    }
+}
@@ -0,0 +1,39 @@
+use clap::Args;
+use gg_tui::Table;
+use crate::Context;
+use crate::output::{self, ScriptOutput};
+use crate::result::{Error, Result};
+
+#[derive(Args)]
+#[command(about = "list scripts of the project")]
+pub(crate) struct ScriptsCommand {}
+
+impl ScriptsCommand {
+    pub(crate) async fn run(&self, ctx: &Context) -> Result<()> {
+        if let Some(err) = &ctx.config_error {
+            return Err(Error::Config(err.clone()));
+        }
+
+        let mut scripts: Vec<_> = ctx.config.scripts.iter().collect();
+        scripts.sort_by_key(|(name, _)| *name);
+
+        if output::is_json() {
+            let scripts: Vec<_> = scripts.into_iter().map(|(name, script)| ScriptOutput { name, script }).collect();
+            output::print_json(&scripts);
+            return Ok(());
+        }
+
+        if scripts.is_empty() {
+            println!("no script declared");
+            return Ok(());
+        }
+
+        let mut table = Table::new().header(["NAME", "COMMAND", "ALIASES"]);
+        for (name, script) in scripts {
+            table.row([name, &script.command, &script.aliases.join(", ")]);
+        }
+        print!("{}", table);
+
+        Ok(())
+    }
+}
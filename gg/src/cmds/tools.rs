@@ -0,0 +1,114 @@
+use clap::{Args, Subcommand};
+use gg_tui::{ep_warning, Table};
+use crate::Context;
+use crate::output::{self, ToolOutput};
+use crate::result::{Error, Result};
+use crate::tool::{is_available, platform, resolve, ToolStore};
+
+#[derive(Args)]
+#[command(about = "manage tools pinned by the project")]
+pub(crate) struct ToolsCommand {
+    #[command(subcommand)]
+    command: ToolsSubcommand,
+}
+
+#[derive(Subcommand)]
+enum ToolsSubcommand {
+    /// download and verify tools, all tools if no name is given
+    Install {
+        names: Vec<String>,
+    },
+    /// list tools of the project
+    List,
+}
+
+impl ToolsCommand {
+    pub(crate) async fn run(&self, ctx: &Context) -> Result<()> {
+        let store = ToolStore::default();
+
+        let mut names: Vec<&String> = ctx.config.tools.keys().collect();
+        names.sort();
+
+        match &self.command {
+            ToolsSubcommand::Install { names: selected } => {
+                if let Some(name) = selected.iter().find(|name| !ctx.config.tools.contains_key(*name)) {
+                    return Err(Error::NotDeclared {
+                        what: format!("tool {}", name),
+                        hint: Some("tools are declared in `tools` of the config".to_string()),
+                    });
+                }
+
+                let mut installed = Vec::new();
+                for name in names {
+                    if !selected.is_empty() && !selected.contains(name) {
+                        continue;
+                    }
+                    // only tools asked for by name fail if not available
+                    let tool = &ctx.config.tools[name];
+                    if selected.is_empty() && !is_available(tool) {
+                        ep_warning!("tool {} {} is not available for {}, skipped", name, tool.version, platform());
+                        continue;
+                    }
+
+                    let tool = resolve(name, tool)?;
+                    if store.is_installed(&tool) {
+                        if !output::is_json() {
+                            println!("{} {} is installed", tool.name, tool.version);
+                        }
+                    } else {
+                        store.install(&tool).await?;
+                        if !output::is_json() {
+                            println!("installed {} {}", tool.name, tool.version);
+                        }
+                    }
+                    installed.push(ToolOutput {
+                        name: tool.name.to_string(),
+                        version: tool.version.to_string(),
+                        available: true,
+                        binary: Some(store.binary(&tool)),
+                    });
+                }
+
+                if output::is_json() {
+                    output::print_json(&installed);
+                }
+            }
+            ToolsSubcommand::List if output::is_json() => {
+                let tools: Vec<_> = names.into_iter()
+                    .map(|name| {
+                        let tool = &ctx.config.tools[name];
+                        let resolved = resolve(name, tool).ok();
+                        ToolOutput {
+                            name: name.clone(),
+                            version: tool.version.clone(),
+                            available: resolved.is_some(),
+                            binary: resolved.filter(|tool| store.is_installed(tool)).map(|tool| store.binary(&tool)),
+                        }
+                    })
+                    .collect();
+                output::print_json(&tools);
+            }
+            ToolsSubcommand::List => {
+                if names.is_empty() {
+                    println!("no tool declared");
+                    return Ok(());
+                }
+
+                let mut table = Table::new().header(["NAME", "VERSION", "STATE"]);
+                for name in names {
+                    let tool = &ctx.config.tools[name];
+                    let state = match resolve(name, tool) {
+                        Ok(tool) if store.is_installed(&tool) => store.binary(&tool).display().to_string(),
+                        Ok(_) => "not installed".to_string(),
+                        Err(_) if !is_available(tool) => format!("not available for {}", platform()),
+                        Err(err) => err.to_string(),
+                    };
+                    table.row([name, &tool.version, &state]);
+                }
+                print!("{}", table);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use gg_config::{Config, LoadedConfig, OptionalLoadedConfig};
+use gg_git::{backend, Backend};
+use gg_tui::ep_warning;
+use tracing::debug;
+use crate::GlobalArgs;
+
+/// state resolved once per process and shared by all subcommands
+pub struct Context {
+    /// the directory gg is started in
+    pub cwd: PathBuf,
+    /// the repo root, `None` if not in a git repository
+    pub root: Option<PathBuf>,
+    /// the git dir of the checkout at root, where gg keeps per-checkout state like the run history
+    pub git_dir: Option<PathBuf>,
+    /// loaded config, default config if no config file is found
+    pub config: Config,
+    /// path of the loaded config file
+    pub config_path: Option<PathBuf>,
+    /// error occurred when loading config, `config` is the default config in this case
+    pub config_error: Option<Arc<gg_config::Error>>,
+}
+
+impl Context {
+    /// resolve the context for the current directory, `global` must have been applied already
+    pub async fn new(global: &GlobalArgs) -> Context {
+        Context::load(env::current_dir().unwrap(), global.config.clone()).await
+    }
+
+    pub async fn new_in<P: Into<PathBuf>>(cwd: P) -> Context {
+        Context::load(cwd.into(), None).await
+    }
+
+    /// `config_file` is loaded instead of looking for a config file in the root if set
+    async fn load(cwd: PathBuf, config_file: Option<PathBuf>) -> Context {
+
+        let (root, git_dir) = match Backend::work_tree(&backend(&cwd)).await {
+            Ok(work_tree) => (Some(work_tree.root), Some(work_tree.git_dir)),
+            Err(err) if err.is_not_repository() || err.is_git_not_found() => (None, None),
+            Err(err) => {
+                ep_warning!("failed to get git root: {}", err);
+                (None, None)
+            }
+        };
+
+        let loaded = match config_file {
+            Some(path) => {
+                let path = cwd.join(path);
+                gg_config::load(&path).await.map(|config| Some(LoadedConfig { path, config }))
+            }
+            None => gg_config::auto_load(root.as_ref().unwrap_or(&cwd)).await,
+        };
+        let (config, config_path, config_error) = match loaded {
+            Ok(loaded) => {
+                let path = loaded.as_ref().map(|c| c.path.clone());
+                (loaded.get(), path, None)
+            }
+            Err(err) => (Config::default(), None, Some(Arc::new(err))),
+        };
+
+        debug!(cwd = %cwd.display(), root = ?root, config = ?config_path, "context resolved");
+
+        Context {
+            cwd,
+            root,
+            git_dir,
+            config,
+            config_path,
+            config_error,
+        }
+    }
+
+    /// the directory scripts run in: the repo root, or cwd if not in a git repository
+    pub fn work_dir(&self) -> &Path {
+        self.root.as_ref().unwrap_or(&self.cwd)
+    }
+}
@@ -0,0 +1,182 @@
+mod registry;
+mod install;
+mod requirement;
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use cargo_util::ProcessBuilder;
+use gg_tui::ep_warning;
+use tracing::debug;
+use crate::Context;
+use crate::result::{Error, Result};
+
+pub(crate) use registry::{Registry, Source, validate_name};
+pub(crate) use install::install;
+pub(crate) use requirement::{check, install_hint, RequirementStatus};
+
+/// executable name of an extension
+pub(crate) fn executable_name(name: &str) -> String {
+    format!("gg-{}", name)
+}
+
+/// find the executable of extension `gg <name>`
+///
+/// Extensions installed into gg's extensions dir take precedence over those on PATH.
+pub(crate) fn find(name: &str) -> Option<PathBuf> {
+    find_in(name, Registry::default().bin_dir(), std::env::var_os("PATH"))
+}
+
+fn find_in(name: &str, bin_dir: PathBuf, path: Option<OsString>) -> Option<PathBuf> {
+    let executable = executable_name(name);
+    let cwd = std::env::current_dir().ok()?;
+
+    let found = which::which_in(&executable, Some(bin_dir), &cwd)
+        .or_else(|_| which::which_in(&executable, path, &cwd))
+        .ok();
+    debug!("looking for extension {}: {:?}", executable, found);
+    found
+}
+
+/// names of all extensions that `find` can find, installed or on PATH
+pub(crate) fn list_available() -> Vec<String> {
+    let mut dirs = vec![Registry::default().bin_dir()];
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+
+    let mut names: Vec<String> = dirs.iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            let name = file_name.strip_prefix("gg-")?;
+            Some(name.strip_suffix(std::env::consts::EXE_SUFFIX).unwrap_or(name).to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// warn about extensions required by the config but missing or outdated
+pub(crate) fn warn_unsatisfied(ctx: &Context) {
+    if ctx.config.extensions.is_empty() {
+        return;
+    }
+
+    let registry = Registry::default();
+    let mut names: Vec<&String> = ctx.config.extensions.keys().collect();
+    names.sort();
+
+    for name in names {
+        let requirement = &ctx.config.extensions[name];
+        match check(&registry, name, requirement) {
+            Ok(RequirementStatus::Satisfied) => {}
+            Ok(RequirementStatus::Missing) => {
+                ep_warning!("extension {} is required but not installed, {}", name, install_hint(name, requirement));
+            }
+            Ok(RequirementStatus::Outdated { installed }) => {
+                ep_warning!(
+                    "extension {} {} is installed but {} is required, {}",
+                    name, installed, requirement.version.as_deref().unwrap_or("*"), install_hint(name, requirement),
+                );
+            }
+            Err(err) => ep_warning!("{}", err),
+        }
+    }
+}
+
+/// run the extension executable with args, replacing current process if possible
+///
+/// Context is passed to the extension with environment variables:
+///
+/// - `GG`: path of the gg executable
+/// - `GG_VERSION`: version of gg
+/// - `GG_ROOT`: the repo root, unset if not in a git repository
+/// - `GG_CONFIG`: path of the loaded config file, unset if no config file is found
+pub(crate) fn exec(path: PathBuf, args: &[OsString], ctx: &Context) -> Result<()> {
+    if let Err(err) = process(&path, args, ctx).exec_replace() {
+        return Err(Error::Spawn { program: path.display().to_string(), source: io::Error::other(format!("{:#}", err)) });
+    }
+
+    Ok(())
+}
+
+fn process(path: &Path, args: &[OsString], ctx: &Context) -> ProcessBuilder {
+    let mut process = ProcessBuilder::new(path);
+    process.args(args).cwd(&ctx.cwd).env("GG_VERSION", env!("CARGO_PKG_VERSION"));
+
+    if let Ok(gg) = std::env::current_exe() {
+        process.env("GG", gg);
+    }
+    // not inherited from a gg running this gg
+    match &ctx.root {
+        Some(root) => process.env("GG_ROOT", root),
+        None => process.env_remove("GG_ROOT"),
+    };
+    match &ctx.config_path {
+        Some(config_path) => process.env("GG_CONFIG", config_path),
+        None => process.env_remove("GG_CONFIG"),
+    };
+
+    process
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::*;
+
+    fn setup(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gg-test-extension-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[cfg(unix)]
+    fn write_executable(path: &Path, content: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find() {
+        let dir = setup("find");
+        let bin_dir = dir.join("registry").join("bin");
+        let path_dir = dir.join("path");
+        write_executable(&bin_dir.join("gg-foo"), "#!/bin/sh\n");
+        write_executable(&path_dir.join("gg-foo"), "#!/bin/sh\n");
+        write_executable(&path_dir.join("gg-bar"), "#!/bin/sh\n");
+        let path = Some(path_dir.clone().into_os_string());
+
+        // installed extensions come before PATH
+        assert_eq!(find_in("foo", bin_dir.clone(), path.clone()), Some(bin_dir.join("gg-foo")));
+        assert_eq!(find_in("bar", bin_dir.clone(), path.clone()), Some(path_dir.join("gg-bar")));
+        assert_eq!(find_in("baz", bin_dir, path), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_process_env() {
+        let dir = setup("env");
+        fs::write(dir.join(".ggrc.json"), "{}").unwrap();
+        let extension = dir.join("gg-env");
+        write_executable(&extension, "#!/bin/sh\necho \"$1 $GG_VERSION $GG_CONFIG root=$GG_ROOT\"\ntest -x \"$GG\"\n");
+
+        assert!(std::process::Command::new("git").args(["init", "-q"]).current_dir(&dir).status().unwrap().success());
+
+        let ctx = Context::new_in(&dir).await;
+        assert_eq!(ctx.root.as_deref(), Some(dir.as_path()));
+        let output = process(&extension, &["arg".into()], &ctx).exec_with_output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        let config = ctx.config_path.as_ref().unwrap();
+        assert_eq!(stdout.trim(), format!("arg {} {} root={}", env!("CARGO_PKG_VERSION"), config.display(), dir.display()));
+    }
+}
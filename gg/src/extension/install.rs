@@ -0,0 +1,272 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use tokio::process::Command;
+use gg_git::G;
+use gg_tui::ep_step;
+use crate::{archive, output};
+use crate::extension::executable_name;
+use crate::extension::registry::{Manifest, Registry, remove_if_exists, Source, validate_name};
+use crate::result::{error, Error, Result};
+
+/// optional `gg-extension.json` in the root of an extension's source
+#[derive(Deserialize, Default)]
+struct ExtensionInfo {
+    version: Option<String>,
+    /// command to build the executable, e.g. `make release`
+    build: Option<String>,
+    /// path of the executable relative to the source root
+    bin: Option<PathBuf>,
+}
+
+const INFO_FILENAME: &str = "gg-extension.json";
+
+impl Source {
+    /// guess the source kind of a cli argument
+    pub(crate) fn parse(s: &str) -> Source {
+        let path = Path::new(s);
+        if path.is_dir() {
+            Source::Dir { path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()) }
+        } else if path.is_file() {
+            Source::Archive { path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()) }
+        } else {
+            Source::Git { url: s.to_string() }
+        }
+    }
+
+    /// extension name derived from the source, e.g. `foo` for `https://example.com/gg-foo.git`
+    pub(crate) fn default_name(&self) -> String {
+        let last = match self {
+            Source::Dir { path } | Source::Archive { path } => {
+                path.file_name().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+            }
+            Source::Git { url } => {
+                url.trim_end_matches('/').rsplit(['/', ':']).next().unwrap_or_default().to_string()
+            }
+        };
+
+        let mut name = last.as_str();
+        for suffix in [".git", ".tar.gz", ".tgz", ".zip"] {
+            name = name.strip_suffix(suffix).unwrap_or(name);
+        }
+        name.strip_prefix("gg-").unwrap_or(name).to_string()
+    }
+}
+
+/// install (or reinstall) an extension from source, building it if needed
+pub(crate) async fn install(registry: &Registry, source: Source, name: &str) -> Result<Manifest> {
+    // checked before anything is removed from `src_dir`
+    validate_name(name)?;
+    let installed = registry.get(name)?.is_some();
+
+    let result = do_install(registry, source, name).await;
+    if result.is_err() && !installed {
+        // don't leave sources of a failed installation behind
+        let _ = remove_if_exists(&registry.src_dir(name));
+    }
+
+    result
+}
+
+async fn do_install(registry: &Registry, source: Source, name: &str) -> Result<Manifest> {
+    let root = prepare(registry, &source, name).await?;
+
+    let info = read_info(&root)?;
+    build(&root, &info).await?;
+    let binary = find_binary(&root, &[name, &source.default_name()], &info)
+        .ok_or_else(|| error(&format!(
+            "cannot find executable {} in {}, set `bin` in {} if it's named differently",
+            executable_name(name), root.display(), INFO_FILENAME,
+        )))?;
+
+    let version = match info.version.or_else(|| cargo_version(&root)) {
+        Some(version) => version,
+        None => git_version(&root, &source).await.unwrap_or_else(|| "unknown".to_string()),
+    };
+
+    let bin_dir = registry.bin_dir();
+    let installed = bin_dir.join(format!("{}{}", executable_name(name), std::env::consts::EXE_SUFFIX));
+    fs::create_dir_all(&bin_dir)
+        .and_then(|_| fs::copy(&binary, &installed))
+        .map_err(|source| Error::Io { action: format!("cannot install {}", binary.display()), source })?;
+
+    let manifest = Manifest {
+        name: name.to_string(),
+        version,
+        source,
+        binary: installed,
+    };
+    registry.save(&manifest)?;
+
+    Ok(manifest)
+}
+
+/// get the source root, cloning or extracting into the registry if needed
+async fn prepare(registry: &Registry, source: &Source, name: &str) -> Result<PathBuf> {
+    match source {
+        Source::Dir { path } => Ok(path.clone()),
+        Source::Archive { path } => {
+            let dir = registry.src_dir(name);
+            remove_if_exists(&dir)?;
+            archive::extract(path, &path.to_string_lossy(), &dir)?;
+            Ok(single_child_dir(&dir).unwrap_or(dir))
+        }
+        Source::Git { url } => {
+            let dir = registry.src_dir(name);
+            if dir.join(".git").exists() {
+                let mut args = vec!["pull".to_string(), "--ff-only".to_string()];
+                if output::is_json() {
+                    // `git pull` reports on stdout
+                    args.push("--quiet".to_string());
+                }
+                G::new(&dir).passthrough(args).await
+                    .map_err(|source| Error::Git { action: format!("failed to update {}", url), source })?;
+            } else {
+                remove_if_exists(&dir)?;
+                fs::create_dir_all(&dir)
+                    .map_err(|source| Error::Io { action: format!("cannot create {}", dir.display()), source })?;
+                let args = vec!["clone".to_string(), "--depth".to_string(), "1".to_string(), url.clone(), ".".to_string()];
+                G::new(&dir).passthrough(args).await
+                    .map_err(|source| Error::Git { action: format!("failed to clone {}", url), source })?;
+            }
+            Ok(dir)
+        }
+    }
+}
+
+/// archives often wrap everything in a single top-level directory
+fn single_child_dir(dir: &Path) -> Option<PathBuf> {
+    let mut entries = fs::read_dir(dir).ok()?;
+    let only = entries.next()?.ok()?.path();
+    if entries.next().is_none() && only.is_dir() {
+        Some(only)
+    } else {
+        None
+    }
+}
+
+fn read_info(root: &Path) -> Result<ExtensionInfo> {
+    let path = root.join(INFO_FILENAME);
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|err| Error::Io { action: format!("invalid {}", path.display()), source: err.into() }),
+        Err(_) => Ok(ExtensionInfo::default()),
+    }
+}
+
+/// find the executable named after one of `names` if not specified by `gg-extension.json`
+fn find_binary(root: &Path, names: &[&str], info: &ExtensionInfo) -> Option<PathBuf> {
+    if let Some(bin) = &info.bin {
+        let bin = root.join(bin);
+        return bin.is_file().then_some(bin);
+    }
+
+    let dirs = [root.to_path_buf(), root.join("bin"), root.join("target").join("release")];
+    names.iter()
+        .map(|name| format!("{}{}", executable_name(name), std::env::consts::EXE_SUFFIX))
+        .flat_map(|executable| dirs.iter().map(move |dir| dir.join(&executable)))
+        .find(|path| path.is_file())
+}
+
+/// run the build command of the extension, do nothing for prebuilt extensions
+async fn build(root: &Path, info: &ExtensionInfo) -> Result<()> {
+    let args = match &info.build {
+        Some(build) => shlex::split(build).ok_or_else(|| error(&format!("invalid build command: {}", build)))?,
+        None if root.join("Cargo.toml").is_file() => vec!["cargo".to_string(), "build".to_string(), "--release".to_string()],
+        None => return Ok(()),
+    };
+    if args.is_empty() {
+        return Ok(());
+    }
+
+    ep_step!("{}", args.join(" "));
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]).current_dir(root);
+    if output::is_json() {
+        // keep stdout for the result of gg
+        command.stdout(std::io::stderr());
+    }
+    let status = command
+        .status()
+        .await
+        .map_err(|source| Error::Spawn { program: args[0].clone(), source })?;
+
+    if !status.success() {
+        return Err(Error::Build { root: root.to_path_buf(), status });
+    }
+
+    Ok(())
+}
+
+fn cargo_version(root: &Path) -> Option<String> {
+    let content = fs::read_to_string(root.join("Cargo.toml")).ok()?;
+    let manifest: toml::Table = toml::from_str(&content).ok()?;
+    manifest.get("package")?.get("version")?.as_str().map(String::from)
+}
+
+async fn git_version(root: &Path, source: &Source) -> Option<String> {
+    if !matches!(source, Source::Git { .. }) {
+        return None;
+    }
+
+    let args = vec!["rev-parse".to_string(), "--short".to_string(), "HEAD".to_string()];
+    let output = G::new(root).run(args).await.ok()?;
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_name() {
+        let name = |s: &str| Source::Git { url: s.to_string() }.default_name();
+        assert_eq!(name("https://github.com/ImSingee/gg-foo.git"), "foo");
+        assert_eq!(name("git@github.com:ImSingee/bar"), "bar");
+        assert_eq!(name("https://example.com/baz/"), "baz");
+
+        let name = |s: &str| Source::Archive { path: PathBuf::from(s) }.default_name();
+        assert_eq!(name("/tmp/gg-foo.tar.gz"), "foo");
+        assert_eq!(name("/tmp/foo.zip"), "foo");
+        assert_eq!(name("/"), "");
+    }
+
+    #[tokio::test]
+    async fn test_install_invalid_name() {
+        let dir = std::env::temp_dir().join("gg-test-extension-install-invalid");
+        let _ = fs::remove_dir_all(&dir);
+        let outside = dir.join("outside");
+        fs::create_dir_all(&outside).unwrap();
+
+        // `src/../../outside` would be removed before extracting if the name was not checked
+        let registry = Registry::new(dir.join("registry"));
+        let source = Source::Archive { path: dir.join("missing.tar.gz") };
+        for name in ["../../outside", "..", ""] {
+            assert!(install(&registry, source.clone(), name).await.is_err(), "{}", name);
+        }
+        assert!(outside.is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_install_dir() {
+        let dir = std::env::temp_dir().join("gg-test-extension-install");
+        let _ = fs::remove_dir_all(&dir);
+        let src = dir.join("gg-hello");
+        fs::create_dir_all(src.join("bin")).unwrap();
+        fs::write(src.join("bin").join(executable_name("hello")), "#!/bin/sh\necho hello\n").unwrap();
+        fs::write(src.join(INFO_FILENAME), r#"{"version": "1.2.3"}"#).unwrap();
+
+        let registry = Registry::new(dir.join("registry"));
+        let source = Source::parse(src.to_str().unwrap());
+        assert_eq!(source.default_name(), "hello");
+
+        let manifest = install(&registry, source, "hello").await.unwrap();
+        assert_eq!(manifest.version, "1.2.3");
+        assert!(manifest.binary.is_file());
+        assert_eq!(registry.list().unwrap(), vec![manifest.clone()]);
+
+        registry.remove(&manifest).unwrap();
+        assert!(!manifest.binary.exists());
+        assert!(registry.list().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::output::{ExtensionOutput, SourceOutput};
+use crate::paths::data_dir;
+use crate::result::{error, Error, Result};
+
+/// where an extension is installed from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum Source {
+    /// a local directory, built in place if needed
+    Dir { path: PathBuf },
+    /// a local `.tar.gz`, `.tgz` or `.zip` archive
+    Archive { path: PathBuf },
+    /// a git repository, cloned into the registry
+    Git { url: String },
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Dir { path } => write!(f, "dir {}", path.display()),
+            Source::Archive { path } => write!(f, "archive {}", path.display()),
+            Source::Git { url } => write!(f, "git {}", url),
+        }
+    }
+}
+
+/// record of an installed extension
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Manifest {
+    pub name: String,
+    pub version: String,
+    pub source: Source,
+    /// the installed executable
+    pub binary: PathBuf,
+}
+
+/// installed extensions under `<data dir>/extensions`
+///
+/// - `bin/gg-<name>`: executables, searched by `extension::find`
+/// - `manifests/<name>.json`: one `Manifest` per extension
+/// - `src/<name>`: sources cloned or extracted by gg
+pub(crate) struct Registry {
+    dir: PathBuf,
+}
+
+impl Registry {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Registry {
+        Registry { dir: dir.into() }
+    }
+
+    pub fn bin_dir(&self) -> PathBuf {
+        self.dir.join("bin")
+    }
+
+    pub fn src_dir(&self, name: &str) -> PathBuf {
+        self.dir.join("src").join(name)
+    }
+
+    fn manifest_path(&self, name: &str) -> PathBuf {
+        self.dir.join("manifests").join(format!("{}.json", name))
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<Manifest>> {
+        validate_name(name)?;
+        read_manifest(&self.manifest_path(name))
+    }
+
+    /// all installed extensions, sorted by name
+    pub fn list(&self) -> Result<Vec<Manifest>> {
+        let entries = match fs::read_dir(self.dir.join("manifests")) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(Error::Io { action: "cannot read extension registry".to_string(), source }),
+        };
+
+        let mut manifests = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|source| Error::Io { action: "cannot read extension registry".to_string(), source })?
+                .path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(manifest) = read_manifest(&path)? {
+                    manifests.push(manifest);
+                }
+            }
+        }
+        manifests.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(manifests)
+    }
+
+    pub fn save(&self, manifest: &Manifest) -> Result<()> {
+        validate_name(&manifest.name)?;
+        let path = self.manifest_path(&manifest.name);
+        let content = serde_json::to_string_pretty(manifest).unwrap();
+
+        fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| fs::write(&path, content))
+            .map_err(|source| Error::Io { action: format!("cannot write {}", path.display()), source })
+    }
+
+    /// remove the manifest, the executable and the sources managed by gg
+    pub fn remove(&self, manifest: &Manifest) -> Result<()> {
+        validate_name(&manifest.name)?;
+        remove_if_exists(&manifest.binary)?;
+        remove_if_exists(&self.src_dir(&manifest.name))?;
+        remove_if_exists(&self.manifest_path(&manifest.name))
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::new(data_dir().join("extensions"))
+    }
+}
+
+/// names end up in paths of the registry, so only a safe subset is allowed
+pub(crate) fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(error(&format!("invalid extension name '{}', only letters, digits, '-' and '_' are allowed", name)))
+    }
+}
+
+fn read_manifest(path: &Path) -> Result<Option<Manifest>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(Error::Io { action: format!("cannot read {}", path.display()), source }),
+    };
+
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|err| Error::Io { action: format!("invalid extension manifest {}", path.display()), source: err.into() })
+}
+
+pub(crate) fn remove_if_exists(path: &Path) -> Result<()> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(source) => Err(Error::Io { action: format!("cannot remove {}", path.display()), source }),
+    }
+}
+
+impl<'a> From<&'a Manifest> for ExtensionOutput<'a> {
+    fn from(manifest: &'a Manifest) -> Self {
+        let source = match &manifest.source {
+            Source::Dir { path } => SourceOutput::Dir { path },
+            Source::Archive { path } => SourceOutput::Archive { path },
+            Source::Git { url } => SourceOutput::Git { url },
+        };
+        ExtensionOutput { name: &manifest.name, version: &manifest.version, source, binary: &manifest.binary }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("foo").is_ok());
+        assert!(validate_name("foo-bar_2").is_ok());
+        for name in ["", ".", "..", "../foo", "foo/bar", "/tmp", "foo\\bar", "foo.bar", "foo bar"] {
+            assert!(validate_name(name).is_err(), "{}", name);
+        }
+
+        let dir = std::env::temp_dir().join("gg-test-extension-registry");
+        let registry = Registry::new(&dir);
+        assert!(registry.get("..").is_err());
+        let manifest = Manifest {
+            name: "../..".to_string(),
+            version: "1.0.0".to_string(),
+            source: Source::Dir { path: dir.clone() },
+            binary: dir.join("bin").join("gg-foo"),
+        };
+        assert!(registry.save(&manifest).is_err());
+        assert!(registry.remove(&manifest).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use gg_config::Extension;
+use semver::{Version, VersionReq};
+use crate::extension::registry::{Manifest, Registry};
+use crate::extension::{executable_name, find};
+use crate::result::{Error, Result};
+
+/// state of an extension declared in `extensions` of the config
+#[derive(Debug, PartialEq)]
+pub(crate) enum RequirementStatus {
+    Satisfied,
+    Missing,
+    /// installed, but the version doesn't satisfy the requirement
+    Outdated { installed: String },
+}
+
+/// parse the version requirement, `None` means any version
+pub(crate) fn version_req(name: &str, requirement: &Extension) -> Result<Option<VersionReq>> {
+    match &requirement.version {
+        None => Ok(None),
+        Some(req) => VersionReq::parse(req)
+            .map(Some)
+            .map_err(|source| Error::VersionRequirement {
+                what: format!("extension {}", name),
+                requirement: req.clone(),
+                source,
+            }),
+    }
+}
+
+pub(crate) fn matches(req: &Option<VersionReq>, version: &str) -> bool {
+    match req {
+        None => true,
+        Some(req) => Version::parse(version.trim_start_matches('v')).is_ok_and(|v| req.matches(&v)),
+    }
+}
+
+pub(crate) fn check(registry: &Registry, name: &str, requirement: &Extension) -> Result<RequirementStatus> {
+    let req = version_req(name, requirement)?;
+
+    Ok(match registry.get(name)? {
+        Some(Manifest { version, .. }) if matches(&req, &version) => RequirementStatus::Satisfied,
+        Some(Manifest { version, .. }) => RequirementStatus::Outdated { installed: version },
+        // extensions not managed by gg have no known version
+        None if req.is_none() && find(name).is_some() => RequirementStatus::Satisfied,
+        None => RequirementStatus::Missing,
+    })
+}
+
+/// how to install the extension, shown along with problems
+pub(crate) fn install_hint(name: &str, requirement: &Extension) -> String {
+    match &requirement.source {
+        Some(_) => "run `gg ext sync` to install it".to_string(),
+        None => format!("install it with `gg ext install <source> --name {}` or put {} in PATH", name, executable_name(name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let req = |s: &str| Some(VersionReq::parse(s).unwrap());
+
+        assert!(matches(&None, "unknown"));
+        assert!(matches(&req("^1.2"), "1.3.0"));
+        assert!(matches(&req("^1.2"), "v1.2.1"));
+        assert!(!matches(&req("^1.2"), "1.1.0"));
+        assert!(!matches(&req(">=0.3"), "abc1234"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use clap::{ArgAction, Args, Command, FromArgMatches, ValueEnum};
+use gg_tui::Verbosity;
+use crate::output::{self, OutputFormat};
+use crate::result::Error;
+
+/// options accepted by every subcommand, given before the subcommand or script name
+#[derive(Args, Default)]
+pub struct GlobalArgs {
+    /// run as if gg was started in <DIR>
+    #[arg(short = 'C', long = "cwd", value_name = "DIR", global = true)]
+    pub cwd: Option<PathBuf>,
+    /// use this config file instead of looking for one in the repo root
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+    /// only print errors and the output of commands
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// print more details about what gg is doing
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+    /// when to use colors
+    #[arg(long, value_name = "WHEN", value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    pub color: ColorChoice,
+    /// format of what gg prints, json also implies --quiet unless --verbose is given
+    #[arg(long, value_name = "FORMAT", value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl GlobalArgs {
+    /// parse global options from args, before the context is created
+    ///
+    /// Parsing is lenient, errors are reported when the full command line is parsed later.
+    pub fn parse_from<I, T>(cmd: &Command, args: I) -> GlobalArgs
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        // defaults are not filled in when errors are ignored, only update what is given
+        let mut global = GlobalArgs::default();
+        if let Ok(matches) = cmd.clone().ignore_errors(true).try_get_matches_from(args) {
+            let _ = global.update_from_arg_matches(&matches);
+        }
+        global
+    }
+
+    /// apply options that affect the whole process, exit if failed
+    pub fn apply(&self) {
+        gg_tui::set_colors(match self.color {
+            ColorChoice::Auto => None,
+            ColorChoice::Always => Some(true),
+            ColorChoice::Never => Some(false),
+        });
+
+        output::set_format(self.output);
+        gg_tui::set_verbosity(match (self.quiet, self.verbose, self.output) {
+            (true, _, _) => Verbosity::Quiet,
+            // warnings on stderr would get mixed with json errors
+            (false, 0, OutputFormat::Json) => Verbosity::Quiet,
+            (false, 0, OutputFormat::Text) => Verbosity::Normal,
+            (false, _, _) => Verbosity::Verbose,
+        });
+        crate::logging::init(self.verbose);
+
+        if let Some(cwd) = &self.cwd {
+            if let Err(source) = std::env::set_current_dir(cwd) {
+                Error::Io { action: format!("cannot change to directory {}", cwd.display()), source }.exit();
+            }
+        }
+    }
+}
+
+/// index of the subcommand (or script) in args, skipping options of the top-level command
+///
+/// `--` is returned as a subcommand too.
+pub(crate) fn subcommand_index(cmd: &Command, args: &[OsString]) -> Option<usize> {
+    let takes_value = |arg: &clap::Arg| arg.get_action().takes_values();
+
+    let mut i = 1;
+    while i < args.len() {
+        // options of gg are ascii, which is kept as is
+        let arg = args[i].to_string_lossy();
+        if arg == "--" || arg == "-" || !arg.starts_with('-') {
+            return Some(i);
+        }
+
+        let consumes_next = match arg.strip_prefix("--") {
+            Some(long) => !long.contains('=') && cmd.get_arguments().any(|a| a.get_long() == Some(long) && takes_value(a)),
+            // short flags can be combined, the first one taking a value consumes the rest, e.g. `-qC dir` or `-Cdir`
+            None => {
+                let shorts: Vec<char> = arg[1..].chars().collect();
+                shorts.iter()
+                    .position(|c| cmd.get_arguments().any(|a| a.get_short() == Some(*c) && takes_value(a)))
+                    .is_some_and(|p| p == shorts.len() - 1)
+            }
+        };
+
+        i += if consumes_next { 2 } else { 1 };
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_cmd;
+
+    fn args(s: &str) -> Vec<OsString> {
+        s.split(' ').map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_parse_from() {
+        let global = GlobalArgs::parse_from(&get_cmd(), args("gg -q -C /tmp --color never --output json build --verbose"));
+        assert!(global.quiet);
+        assert!(global.output == OutputFormat::Json);
+        assert_eq!(global.cwd, Some(PathBuf::from("/tmp")));
+        assert!(matches!(global.color, ColorChoice::Never));
+        // options after a script belong to the script
+        assert_eq!(global.verbose, 0);
+
+        // options given before an invalid one are kept
+        let global = GlobalArgs::parse_from(&get_cmd(), args("gg --output json --bogus"));
+        assert!(global.output == OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_subcommand_index() {
+        let cmd = get_cmd();
+        assert_eq!(subcommand_index(&cmd, &args("gg build")), Some(1));
+        assert_eq!(subcommand_index(&cmd, &args("gg -C build build")), Some(3));
+        assert_eq!(subcommand_index(&cmd, &args("gg -qC build -v build")), Some(4));
+        assert_eq!(subcommand_index(&cmd, &args("gg -Cbuild --config=x.json build")), Some(3));
+        assert_eq!(subcommand_index(&cmd, &args("gg --color never -- build")), Some(3));
+        assert_eq!(subcommand_index(&cmd, &args("gg -q")), None);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            let mut args = args("gg -C");
+            args.push(OsString::from_vec(b"\xffdir".to_vec()));
+            args.push(OsString::from_vec(b"build\xff".to_vec()));
+            assert_eq!(subcommand_index(&cmd, &args), Some(3));
+        }
+    }
+}
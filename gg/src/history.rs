@@ -0,0 +1,171 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use gg_git::G;
+use crate::output::HistoryOutput;
+use crate::result::{Error, Result};
+
+/// history file is trimmed to this many runs once it grows too big
+const MAX_RUNS: usize = 1000;
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// a finished run of a script
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Run {
+    pub(crate) script: String,
+    pub(crate) args: Vec<String>,
+    /// the directory gg was started in
+    pub(crate) cwd: PathBuf,
+    /// seconds since unix epoch
+    pub(crate) started_at: u64,
+    pub(crate) duration_ms: u64,
+    pub(crate) exit_code: i32,
+    /// commit checked out when the script started, `None` if there is no commit yet
+    pub(crate) head: Option<String>,
+}
+
+/// runs of scripts in a repo, stored as json lines in `.git/gg/history.jsonl`
+pub(crate) struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub(crate) fn new<P: Into<PathBuf>>(path: P) -> History {
+        History { path: path.into() }
+    }
+
+    /// history of the checkout of `git_dir`, each worktree has its own
+    pub(crate) fn for_git_dir(git_dir: &Path) -> History {
+        History::new(git_dir.join("gg").join("history.jsonl"))
+    }
+
+    pub(crate) fn append(&self, run: &Run) -> Result<()> {
+        let map_err = |source| Error::History { action: format!("cannot write {}", self.path.display()), source };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(map_err)?;
+        }
+
+        let too_big = fs::metadata(&self.path).map(|m| m.len() > MAX_FILE_SIZE).unwrap_or(false);
+        if too_big {
+            let mut runs = self.list()?;
+            runs.push(run.clone());
+            let keep = runs.split_off(runs.len().saturating_sub(MAX_RUNS));
+            let mut content = String::new();
+            for run in &keep {
+                content.push_str(&serde_json::to_string(run).unwrap());
+                content.push('\n');
+            }
+            return fs::write(&self.path, content).map_err(map_err);
+        }
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&self.path).map_err(map_err)?;
+        writeln!(file, "{}", serde_json::to_string(run).unwrap()).map_err(map_err)
+    }
+
+    /// all recorded runs, oldest first
+    ///
+    /// Lines that cannot be parsed (e.g. written by another version of gg) are skipped.
+    pub(crate) fn list(&self) -> Result<Vec<Run>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(Error::History { action: format!("cannot read {}", self.path.display()), source }),
+        };
+
+        Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+
+    pub(crate) fn last(&self) -> Result<Option<Run>> {
+        Ok(self.list()?.pop())
+    }
+}
+
+/// the commit checked out at root
+pub(crate) async fn head(root: &Path) -> Option<String> {
+    let args = vec!["rev-parse".to_string(), "--verify".to_string(), "-q".to_string(), "HEAD".to_string()];
+    let output = G::new(root).run(args).await.ok()?;
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+/// human readable duration, e.g. `850ms`, `1.2s` or `3m05s`
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let ms = duration.as_millis();
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// how long ago a unix timestamp is, e.g. `5m ago`
+pub(crate) fn format_age(timestamp: u64) -> String {
+    let secs = now().saturating_sub(timestamp);
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+impl<'a> From<&'a Run> for HistoryOutput<'a> {
+    fn from(run: &'a Run) -> Self {
+        HistoryOutput {
+            script: &run.script,
+            args: &run.args,
+            cwd: &run.cwd,
+            started_at: run.started_at,
+            duration_ms: run.duration_ms,
+            exit_code: run.exit_code,
+            head: run.head.as_deref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(script: &str, exit_code: i32) -> Run {
+        Run {
+            script: script.to_string(),
+            args: vec!["--flag".to_string()],
+            cwd: PathBuf::from("/repo"),
+            started_at: 0,
+            duration_ms: 10,
+            exit_code,
+            head: None,
+        }
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = std::env::temp_dir().join("gg-test-history");
+        let _ = fs::remove_dir_all(&dir);
+
+        let history = History::new(dir.join("gg").join("history.jsonl"));
+        assert_eq!(history.last().unwrap(), None);
+
+        history.append(&run("build", 0)).unwrap();
+        history.append(&run("test", 1)).unwrap();
+        assert_eq!(history.list().unwrap(), vec![run("build", 0), run("test", 1)]);
+        assert_eq!(history.last().unwrap(), Some(run("test", 1)));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_duration(Duration::from_millis(1240)), "1.2s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m05s");
+    }
+}
@@ -1,26 +1,140 @@
-use std::env;
+use std::ffi::OsString;
 use clap::{ArgMatches, Args, Command, command, FromArgMatches};
-use cmds::RunCommand;
-use gg_config::{Config, OptionalLoadedConfig};
+use cmds::{CompleteCommand, CompletionsCommand, ConfigCommand, ExtCommand, HistoryCommand, RunCommand, ScriptsCommand, ToolsCommand};
 use gg_tui::ep_warning;
+use semver::{Version, VersionReq};
+use tracing::debug;
 use result::Result;
-use crate::result::{error, exit};
+use crate::alias::Aliases;
+use crate::result::{exit, Error};
 
+pub use context::Context;
+pub use global::GlobalArgs;
+use global::subcommand_index;
+
+mod alias;
+mod archive;
 mod cmds;
+mod context;
+mod extension;
+mod global;
+mod logging;
+mod history;
+mod output;
+mod paths;
+mod process;
 mod result;
+mod suggest;
+mod tool;
 
 pub fn get_cmd() -> Command {
-    command!()
+    GlobalArgs::augment_args(command!())
         .allow_external_subcommands(true)
+        .after_help("\
+Scripts, script aliases, extensions and tools can be run as `gg <name>`, in this order of precedence. \
+Builtin subcommands always come first, use `gg :<script>` or `gg -- <script>` to run a script shadowed by one.")
         .subcommand(
             RunCommand::augment_args(Command::new("run"))
         )
+        .subcommand(
+            ScriptsCommand::augment_args(Command::new("scripts"))
+        )
+        .subcommand(
+            ConfigCommand::augment_args(Command::new("config"))
+        )
+        .subcommand(
+            ExtCommand::augment_args(Command::new("ext"))
+        )
+        .subcommand(
+            ToolsCommand::augment_args(Command::new("tools"))
+        )
+        .subcommand(
+            HistoryCommand::augment_args(Command::new("history"))
+        )
+        .subcommand(
+            CompletionsCommand::augment_args(Command::new("completions"))
+        )
+        .subcommand(
+            CompleteCommand::augment_args(Command::new("complete-names")).hide(true)
+        )
 }
 
-pub async fn run(mut cmd: Command, matches: ArgMatches) {
+/// names of subcommands built into gg
+pub(crate) fn builtin_subcommands() -> Vec<String> {
+    get_cmd().get_subcommands().filter(|c| !c.is_hide_set()).map(|c| c.get_name().to_string()).collect()
+}
+
+/// fail if this gg doesn't satisfy `gg` of the config
+fn check_version(ctx: &Context) -> Result<()> {
+    let Some(required) = &ctx.config.gg else {
+        return Ok(());
+    };
+
+    let req = VersionReq::parse(required)
+        .map_err(|source| Error::VersionRequirement { what: "gg".to_string(), requirement: required.clone(), source })?;
+    let installed = env!("CARGO_PKG_VERSION");
+    if req.matches(&Version::parse(installed).expect("gg has a semver version")) {
+        return Ok(());
+    }
+
+    let config = ctx.config_path.as_ref().map(|path| path.display().to_string()).unwrap_or_else(|| "the config".to_string());
+    Err(Error::VersionMismatch {
+        what: "gg".to_string(),
+        installed: installed.to_string(),
+        required: required.clone(),
+        hint: Some(format!("install a version of gg matching {}, or update `gg` in {}", required, config)),
+    })
+}
+
+pub async fn run(mut cmd: Command, matches: ArgMatches, ctx: &Context) {
+    // completions must keep working, and the config can still be inspected
+    if !matches!(matches.subcommand_name(), Some("config" | "completions" | "complete-names")) {
+        if let Err(err) = check_version(ctx) {
+            err.exit();
+        }
+    }
+
+    if !matches!(matches.subcommand_name(), Some("ext" | "completions" | "complete-names")) {
+        extension::warn_unsatisfied(ctx);
+    }
+
     let result: Result<()> = match matches.subcommand() {
         Some(("run", m)) => {
-            RunCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run().await
+            RunCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some(("scripts", m)) => {
+            ScriptsCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some(("config", m)) => {
+            ConfigCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some(("ext", m)) => {
+            ExtCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some(("tools", m)) => {
+            ToolsCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some(("history", m)) => {
+            HistoryCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some(("completions", m)) => {
+            CompletionsCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some(("complete-names", m)) => {
+            CompleteCommand::from_arg_matches(m).map_err(|err| err.exit()).unwrap().run(ctx).await
+        }
+        Some((name, m)) => {
+            // get_matches only lets extensions and tools through as external subcommands
+            let args: Vec<OsString> = m.get_many::<OsString>("").into_iter().flatten().cloned().collect();
+            if let Some(path) = extension::find(name) {
+                debug!("{} is an extension at {}", name, path.display());
+                extension::exec(path, &args, ctx)
+            } else if ctx.config.tools.contains_key(name) {
+                debug!("{} is a tool", name);
+                tool::exec(ctx, name, &args).await
+            } else {
+                Err(Error::UnknownSubcommand { name: name.to_string(), hint: None })
+            }
         }
         _ => {
             cmd.print_long_help().expect("cannot print help message");
@@ -32,51 +146,127 @@ pub async fn run(mut cmd: Command, matches: ArgMatches) {
 }
 
 /// like cmd.get_matches, but will try to run subcommand if there is unknown argument
-pub async fn get_matches(cmd: &mut Command) -> ArgMatches {
-    let matches = cmd.get_matches_mut();
+pub async fn get_matches(cmd: &mut Command, ctx: &Context) -> ArgMatches {
+    let original_args: Vec<OsString> = std::env::args_os().collect();
+    let index = subcommand_index(cmd, &original_args);
+    if let Some(new_args) = index.and_then(|index| force_script(&original_args, index)) {
+        debug!("forced script resolution: {:?}", new_args);
+        return cmd.try_get_matches_from_mut(new_args).map_err(|e| Error::from(e).exit()).unwrap();
+    }
 
+    let matches = cmd.try_get_matches_from_mut(&original_args).map_err(|e| Error::from(e).exit()).unwrap();
+
+    if let Some(name) = matches.subcommand_name() {
+        if ctx.config.scripts.contains_key(name) && builtin_subcommands().iter().any(|builtin| builtin == name) {
+            ep_warning!("script {} is shadowed by builtin subcommand {}, run it with `gg :{}`", name, name, name);
+        }
+    }
 
     if let Some((subcommand, _)) = matches.subcommand() {
         if !cmd.get_subcommands().any(|c| c.get_name() == subcommand) {
             // unknown subcommand
 
-            let config = gg_config::auto_load_for_repo(env::current_dir().unwrap()).await;
-            let config = match config {
-                Ok(config) => config.get(),
-                Err(err) => {
-                    ep_warning!("cannot load config: {}", err);
-                    Config::default()
-                }
-            };
+            let config = &ctx.config;
+
 
+            let aliases = Aliases::new(config, &builtin_subcommands());
+            for conflict in aliases.conflicts() {
+                ep_warning!("{}", conflict);
+            }
 
-            // TODO should be three case:
+            // builtin subcommands are handled by clap already, then four cases, in order:
             // 1. scripts
-            // 2. extension
-            // 3. custom tool
+            // 2. script aliases
+            // 3. extension
+            // 4. custom tool
             // or unknown
 
-            // TODO we only use case 1 now
-            let action = {
-                if config.scripts.contains_key(subcommand) {
-                    "run"
-                } else {
-                    // unknown subcommand
-                    let err = error(&format!("unknown subcommand {}", subcommand));
-                    err.exit();
+            let script = if config.scripts.contains_key(subcommand) {
+                debug!("{} is a script", subcommand);
+                subcommand
+            } else if let Some(script) = aliases.get(subcommand) {
+                debug!("{} is an alias of script {}", subcommand, script);
+                script
+            } else if extension::find(subcommand).is_some() || config.tools.contains_key(subcommand) {
+                // handled as external subcommand in `run`
+                if let Some(err) = &ctx.config_error {
+                    ep_warning!("cannot load config: {}", err);
                 }
+                return matches;
+            } else if let Some(err) = &ctx.config_error {
+                // the script may be in the broken config
+                Error::Config(err.clone()).exit();
+            } else {
+                // unknown subcommand
+                suggest::unknown_subcommand(subcommand, ctx).exit();
             };
 
-            let original_args: Vec<String> = std::env::args().collect();
-
+            // `gg [options] <script> args...` => `gg [options] run <script> args...`
+            let index = index.unwrap_or(1);
             let mut new_args = Vec::with_capacity(original_args.len() + 1);
-            new_args.push(original_args[0].clone());
-            new_args.push(action.to_string());
-            new_args.extend_from_slice(&original_args[1..]);
+            new_args.extend_from_slice(&original_args[..index]);
+            new_args.push("run".into());
+            new_args.push(script.into());
+            new_args.extend_from_slice(&original_args[index + 1..]);
 
-            return cmd.try_get_matches_from_mut(new_args).map_err(|e| e.exit()).unwrap();
+            return cmd.try_get_matches_from_mut(new_args).map_err(|e| Error::from(e).exit()).unwrap();
         }
     }
 
     matches
 }
+
+/// `gg :<script> args...` or `gg -- <script> args...` => `gg run <script> args...`,
+/// `index` is the position of the subcommand in args
+///
+/// The explicit syntax skips builtin subcommands, extensions and tools.
+fn force_script(args: &[OsString], index: usize) -> Option<Vec<OsString>> {
+    let first = &args[index];
+    let script = if first == "--" {
+        None
+    } else {
+        // script names are always valid utf-8
+        Some(first.to_str()?.strip_prefix(':')?)
+    };
+
+    let mut new_args = args[..index].to_vec();
+    new_args.push("run".into());
+    new_args.extend(script.filter(|s| !s.is_empty()).map(OsString::from));
+    new_args.extend_from_slice(&args[index + 1..]);
+    Some(new_args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_force_script() {
+        let args = |s: &str| s.split(' ').map(OsString::from).collect::<Vec<_>>();
+
+        assert_eq!(force_script(&args("gg :run a"), 1), Some(args("gg run run a")));
+        assert_eq!(force_script(&args("gg -- run --dry-run"), 1), Some(args("gg run run --dry-run")));
+        assert_eq!(force_script(&args("gg -q :"), 2), Some(args("gg -q run")));
+        assert_eq!(force_script(&args("gg run :a"), 1), None);
+    }
+
+    #[tokio::test]
+    async fn test_check_version() {
+        let dir = std::env::temp_dir().join("gg-test-check-version");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let check = |required: &'static str| {
+            let dir = dir.clone();
+            async move {
+                std::fs::write(dir.join(".ggrc.json"), format!(r#"{{"gg": "{}"}}"#, required)).unwrap();
+                check_version(&Context::new_in(&dir).await)
+            }
+        };
+
+        assert!(check(concat!("=", env!("CARGO_PKG_VERSION"))).await.is_ok());
+        assert!(check(">=0.0.1").await.is_ok());
+        assert_eq!(check(">=999").await.err().unwrap().code(), "version_mismatch");
+        assert_eq!(check("latest").await.err().unwrap().code(), "version_requirement");
+    }
+}
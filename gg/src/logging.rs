@@ -0,0 +1,100 @@
+use std::fmt;
+use std::fs::OpenOptions;
+use std::sync::Mutex;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::registry::LookupSpan;
+use gg_tui::console::console::style;
+use gg_tui::ep_warning;
+
+/// crates whose logs are shown by `--verbose`
+const CRATES: [&str; 3] = ["gg", "gg_config", "gg_git"];
+
+/// directives for the log filter, `GG_LOG` takes precedence over `--verbose`
+///
+/// Returns `None` if logging is off.
+fn filter(gg_log: Option<String>, verbose: u8) -> Option<String> {
+    if let Some(gg_log) = gg_log.filter(|s| !s.is_empty()) {
+        return Some(gg_log);
+    }
+
+    let level = match verbose {
+        0 => return None,
+        1 => "debug",
+        _ => "trace",
+    };
+    Some(CRATES.iter().map(|name| format!("{}={}", name, level)).collect::<Vec<_>>().join(","))
+}
+
+/// send logs to stderr, or to the file at `GG_LOG_FILE` if set
+///
+/// Logging is controlled by `GG_LOG` (e.g. `debug` or `gg_git=trace`) and `--verbose`.
+pub(crate) fn init(verbose: u8) {
+    let directives = match filter(std::env::var("GG_LOG").ok(), verbose) {
+        Some(directives) => directives,
+        None => return,
+    };
+    let filter = EnvFilter::try_new(&directives).unwrap_or_else(|err| {
+        ep_warning!("invalid GG_LOG {}: {}", directives, err);
+        EnvFilter::new("debug")
+    });
+
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+
+    if let Some(path) = std::env::var_os("GG_LOG_FILE") {
+        match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => {
+                builder.with_ansi(false).with_writer(Mutex::new(file)).init();
+                return;
+            }
+            Err(err) => ep_warning!("cannot open log file {}: {}", path.to_string_lossy(), err),
+        }
+    }
+
+    builder
+        .with_ansi(gg_tui::console::console::colors_enabled_stderr())
+        .with_writer(std::io::stderr)
+        .event_format(StderrFormat)
+        .init();
+}
+
+/// compact format for terminals: `DEBUG gg_git: git rev-parse cwd=/repo ...`
+struct StderrFormat;
+
+impl<S, N> FormatEvent<S, N> for StderrFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let metadata = event.metadata();
+        let level = style(format!("{:>5}", metadata.level())).for_stderr();
+        let level = match *metadata.level() {
+            Level::ERROR => level.red(),
+            Level::WARN => level.yellow(),
+            Level::INFO => level.green(),
+            Level::DEBUG => level.blue(),
+            Level::TRACE => level.magenta(),
+        };
+
+        write!(writer, "{} {} ", level, style(format!("{}:", metadata.target())).for_stderr().dim())?;
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        assert_eq!(filter(None, 0), None);
+        assert_eq!(filter(Some(String::new()), 0), None);
+        assert_eq!(filter(None, 1), Some("gg=debug,gg_config=debug,gg_git=debug".to_string()));
+        assert_eq!(filter(None, 3), Some("gg=trace,gg_config=trace,gg_git=trace".to_string()));
+        assert_eq!(filter(Some("gg_git=trace".to_string()), 1), Some("gg_git=trace".to_string()));
+    }
+}
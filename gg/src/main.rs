@@ -1,10 +1,16 @@
-use gg::{get_cmd, get_matches, run};
+use gg::{Context, get_cmd, get_matches, GlobalArgs, run};
 
 
 #[tokio::main]
 async fn main() {
     let mut cmd = get_cmd();
-    let matches = get_matches(&mut cmd).await;
 
-    run(cmd, matches).await;
+    // global options change how the context is resolved, so they are parsed first
+    let global = GlobalArgs::parse_from(&cmd, std::env::args_os());
+    global.apply();
+    let ctx = Context::new(&global).await;
+
+    let matches = get_matches(&mut cmd, &ctx).await;
+
+    run(cmd, matches, &ctx).await;
 }
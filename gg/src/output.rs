@@ -0,0 +1,204 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use clap::ValueEnum;
+use gg_config::{Config, Script};
+use serde::Serialize;
+
+/// format of what gg prints itself, output of scripts and tools is never touched
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// for humans
+    #[default]
+    Text,
+    /// one json value per command on stdout, errors as json objects on stderr
+    Json,
+}
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_format(format: OutputFormat) {
+    JSON.store(format == OutputFormat::Json, Ordering::Relaxed);
+}
+
+pub(crate) fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// print `value` as a single line of json, keys of maps are sorted to keep the output stable
+pub(crate) fn print_json<T: Serialize>(value: &T) {
+    println!("{}", to_json(value));
+}
+
+/// like `print_json`, but to stderr
+pub(crate) fn eprint_json<T: Serialize>(value: &T) {
+    eprintln!("{}", to_json(value));
+}
+
+/// indented json with sorted keys, for text output
+pub(crate) fn to_pretty_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(&to_json(value)).expect("output must be serializable")
+}
+
+fn to_json<T: Serialize>(value: &T) -> serde_json::Value {
+    // `Value` keeps keys in a BTreeMap, while config uses HashMaps
+    serde_json::to_value(value).expect("output must be serializable")
+}
+
+// The structs below are the json output of commands, fields are only ever added.
+
+/// `gg run`, also `gg <script>`
+#[derive(Serialize)]
+pub(crate) struct RunOutput<'a> {
+    pub(crate) script: &'a str,
+    pub(crate) args: &'a [String],
+    pub(crate) exit_code: i32,
+    pub(crate) duration_ms: u64,
+}
+
+/// an item of `gg scripts`
+#[derive(Serialize)]
+pub(crate) struct ScriptOutput<'a> {
+    pub(crate) name: &'a str,
+    #[serde(flatten)]
+    pub(crate) script: &'a Script,
+}
+
+/// `gg config show`
+#[derive(Serialize)]
+pub(crate) struct ConfigOutput<'a> {
+    /// the loaded config file, `None` if the default config is used
+    pub(crate) path: Option<&'a Path>,
+    pub(crate) root: Option<&'a Path>,
+    pub(crate) config: &'a Config,
+}
+
+/// an item of `gg tools list` and `gg tools install`
+#[derive(Serialize)]
+pub(crate) struct ToolOutput {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    /// whether the tool has a download for this platform
+    pub(crate) available: bool,
+    /// the installed executable, `None` if not installed
+    pub(crate) binary: Option<PathBuf>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ExtensionAction {
+    Installed,
+    Upgraded,
+    UpToDate,
+    Removed,
+}
+
+/// an item of `gg ext install`, `remove`, `upgrade` and `sync`
+#[derive(Serialize)]
+pub(crate) struct ExtensionChangeOutput {
+    pub(crate) name: String,
+    pub(crate) action: ExtensionAction,
+    /// `None` for removed extensions
+    pub(crate) version: Option<String>,
+    /// the version before an upgrade
+    pub(crate) previous_version: Option<String>,
+}
+
+/// an item of `gg ext list`
+#[derive(Serialize)]
+pub(crate) struct ExtensionOutput<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) version: &'a str,
+    pub(crate) source: SourceOutput<'a>,
+    /// the installed executable
+    pub(crate) binary: &'a Path,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum SourceOutput<'a> {
+    Dir { path: &'a Path },
+    Archive { path: &'a Path },
+    Git { url: &'a str },
+}
+
+/// an item of `gg history`
+#[derive(Serialize)]
+pub(crate) struct HistoryOutput<'a> {
+    pub(crate) script: &'a str,
+    pub(crate) args: &'a [String],
+    /// the directory gg was started in
+    pub(crate) cwd: &'a Path,
+    /// seconds since unix epoch
+    pub(crate) started_at: u64,
+    pub(crate) duration_ms: u64,
+    pub(crate) exit_code: i32,
+    /// commit checked out when the script started, `None` if there is no commit yet
+    pub(crate) head: Option<&'a str>,
+}
+
+/// `gg run --dry-run` and `--explain`
+#[derive(Serialize)]
+pub(crate) struct PlanOutput<'a> {
+    pub(crate) script: &'a str,
+    pub(crate) config_path: Option<&'a Path>,
+    pub(crate) cwd: SourcedOutput<'a, &'a Path>,
+    /// dirs prepended to PATH, in order
+    pub(crate) path: Vec<SourcedOutput<'a, &'a Path>>,
+    pub(crate) tools: Vec<PlannedToolOutput<'a>>,
+    pub(crate) before: Option<StepOutput<'a>>,
+    pub(crate) command: StepOutput<'a>,
+    pub(crate) on_failure: Option<StepOutput<'a>>,
+    pub(crate) after: Option<StepOutput<'a>>,
+    /// run `after` even if the script fails
+    pub(crate) always: bool,
+}
+
+/// a value and the config key it comes from
+#[derive(Serialize)]
+pub(crate) struct SourcedOutput<'a, T> {
+    pub(crate) value: T,
+    pub(crate) origin: &'a str,
+}
+
+#[derive(Serialize)]
+pub(crate) struct StepOutput<'a> {
+    /// `before`, `command`, `on_failure` or `after`
+    pub(crate) kind: String,
+    pub(crate) command_line: &'a str,
+    pub(crate) argv: SourcedOutput<'a, &'a [String]>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct PlannedToolOutput<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) version: &'a str,
+    pub(crate) url: &'a str,
+    pub(crate) installed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let config = gg_config::parse(r#"{"scripts": {"b": "cargo build", "a": {"command": "cargo test", "aliases": ["t"]}}}"#).unwrap();
+        let output = ConfigOutput { path: None, root: Some(Path::new("/repo")), config: &config };
+        let json = to_json(&output).to_string();
+        assert!(json.starts_with(r#"{"config":{"aliases":{},"extensions":{},"gg":null,"path":[],"scripts":{"a":"#), "{}", json);
+        assert!(json.ends_with(r#""path":null,"root":"/repo"}"#), "{}", json);
+
+        let script = &config.scripts["a"];
+        let json = to_json(&ScriptOutput { name: "a", script }).to_string();
+        assert_eq!(json, r#"{"after":null,"aliases":["t"],"always":false,"before":null,"command":"cargo test","name":"a","on_failure":null,"path":[]}"#);
+
+        let args = ["--all".to_string()];
+        let run = HistoryOutput { script: "b", args: &args, cwd: Path::new("/repo"), started_at: 1, duration_ms: 2, exit_code: 0, head: None };
+        let json = to_json(&run).to_string();
+        assert_eq!(json, r#"{"args":["--all"],"cwd":"/repo","duration_ms":2,"exit_code":0,"head":null,"script":"b","started_at":1}"#);
+
+        let source = SourceOutput::Git { url: "https://example.com/gg-x.git" };
+        let json = to_json(&ExtensionOutput { name: "x", version: "0.1.0", source, binary: Path::new("/bin/gg-x") }).to_string();
+        assert_eq!(json, r#"{"binary":"/bin/gg-x","name":"x","source":{"type":"git","url":"https://example.com/gg-x.git"},"version":"0.1.0"}"#);
+    }
+}
@@ -0,0 +1,86 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use crate::result::{Error, Result};
+
+/// directory for data managed by gg, `$GG_DATA_DIR` or the platform data dir
+pub(crate) fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("GG_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    dirs::data_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("share")))
+        .unwrap_or_else(env::temp_dir)
+        .join("gg")
+}
+
+/// value of `PATH` with dirs prepended, in order
+pub(crate) fn prepend_path(dirs: &[PathBuf]) -> OsString {
+    let mut paths = dirs.to_vec();
+    if let Some(path) = env::var_os("PATH") {
+        paths.extend(env::split_paths(&path));
+    }
+
+    env::join_paths(paths).unwrap_or_else(|_| env::var_os("PATH").unwrap_or_default())
+}
+
+/// built-in presets for `path` in config
+const PATH_PRESETS: [(&str, &[&str]); 3] = [
+    ("node", &["node_modules/.bin"]),
+    #[cfg(not(windows))]
+    ("python", &[".venv/bin"]),
+    #[cfg(windows)]
+    ("python", &[".venv/Scripts"]),
+    ("bin", &["bin"]),
+];
+
+/// resolve `path` entries of config to dirs, relative entries are relative to root
+///
+/// Entries starting with `@` are presets, e.g. `@node` for `node_modules/.bin`.
+pub(crate) fn resolve_path_entries(root: &Path, entries: &[String]) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        match entry.strip_prefix('@') {
+            Some(preset) => {
+                let (_, preset_dirs) = PATH_PRESETS.iter()
+                    .find(|(name, _)| *name == preset)
+                    .ok_or_else(|| Error::InvalidConfig {
+                        message: format!("unknown path preset {}", entry),
+                        hint: Some(format!(
+                            "available presets: {}",
+                            PATH_PRESETS.iter().map(|(name, _)| format!("@{}", name)).collect::<Vec<_>>().join(", "),
+                        )),
+                    })?;
+                dirs.extend(preset_dirs.iter().map(|dir| root.join(dir)));
+            }
+            None => dirs.push(root.join(entry)),
+        }
+    }
+
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path_entries() {
+        let root = Path::new("/repo");
+        let entries = vec!["@node".to_string(), "tools/bin".to_string(), "/opt/bin".to_string()];
+
+        let dirs = resolve_path_entries(root, &entries).unwrap();
+        assert_eq!(dirs, vec![
+            PathBuf::from("/repo/node_modules/.bin"),
+            PathBuf::from("/repo/tools/bin"),
+            PathBuf::from("/opt/bin"),
+        ]);
+
+        let err = resolve_path_entries(root, &["@unknown".to_string()]).err().unwrap();
+        assert_eq!(err.to_string(), "unknown path preset @unknown");
+        assert!(err.hint().unwrap().contains("@node"));
+        assert_eq!(err.exit_code(), crate::result::exit_code::CONFIG);
+    }
+}
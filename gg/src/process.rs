@@ -0,0 +1,135 @@
+use std::io;
+use std::process::ExitStatus;
+use tokio::process::Command;
+
+/// spawn the command and wait for it to exit, forwarding signals received by gg to it
+///
+/// Unlike `exec_replace`, gg keeps running, so it can do more work after the command exits.
+///
+/// On Unix, SIGINT, SIGTERM and SIGHUP are forwarded:
+///
+/// - if gg is the foreground job of a terminal, the child stays in gg's process group, so job
+///   control and interactive programs work as usual. The terminal already sends SIGINT to the
+///   whole group, only SIGTERM and SIGHUP sent to gg itself are forwarded to the child.
+/// - otherwise the child gets its own process group and signals are forwarded to that group,
+///   so everything started by the command is stopped too.
+///
+/// On Windows, the console delivers Ctrl-C to the child directly and gg just ignores it.
+pub(crate) async fn supervise(command: Command) -> io::Result<ExitStatus> {
+    imp::supervise(command).await
+}
+
+/// exit code to report for the status, `128 + n` if the process was killed by signal `n`
+pub(crate) fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    status.code().unwrap_or(1)
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io;
+    use std::process::ExitStatus;
+    use tokio::process::Command;
+    use tokio::signal::unix::{signal, SignalKind};
+    use tracing::debug;
+
+    pub(super) async fn supervise(mut command: Command) -> io::Result<ExitStatus> {
+        // listen before spawning, so there is no window where a signal kills gg but not the child
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut hangup = signal(SignalKind::hangup())?;
+
+        let shared_group = is_foreground();
+        if !shared_group {
+            // SAFETY: setpgid is async-signal-safe
+            unsafe {
+                command.pre_exec(|| match libc::setpgid(0, 0) {
+                    0 => Ok(()),
+                    _ => Err(io::Error::last_os_error()),
+                });
+            }
+        }
+
+        let mut child = command.spawn()?;
+        let pid = child.id().map(|id| id as libc::pid_t);
+
+        loop {
+            let signal = tokio::select! {
+                status = child.wait() => return status,
+                _ = interrupt.recv() => libc::SIGINT,
+                _ = terminate.recv() => libc::SIGTERM,
+                _ = hangup.recv() => libc::SIGHUP,
+            };
+
+            debug!(signal, pid, shared_group, "forwarding signal");
+            if let Some(pid) = pid {
+                // SAFETY: plain syscalls, the child is not reaped until `wait` returns
+                unsafe {
+                    if !shared_group {
+                        libc::killpg(pid, signal);
+                    } else if signal != libc::SIGINT {
+                        libc::kill(pid, signal);
+                    }
+                }
+            }
+        }
+    }
+
+    /// whether gg is the foreground job of the terminal on stdin
+    fn is_foreground() -> bool {
+        // SAFETY: plain syscalls without pointers
+        unsafe {
+            libc::isatty(libc::STDIN_FILENO) == 1 && libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp()
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::io;
+    use std::process::ExitStatus;
+    use tokio::process::Command;
+
+    pub(super) async fn supervise(mut command: Command) -> io::Result<ExitStatus> {
+        let mut child = command.spawn()?;
+
+        loop {
+            tokio::select! {
+                status = child.wait() => return status,
+                // the child is in the same console and receives Ctrl-C itself
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    async fn sh(script: &str) -> ExitStatus {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        supervise(command).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_exit_code() {
+        assert_eq!(exit_code(sh("exit 0").await), 0);
+        assert_eq!(exit_code(sh("exit 3").await), 3);
+        assert_eq!(exit_code(sh("kill -TERM $$").await), 128 + libc::SIGTERM);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_error() {
+        let err = supervise(Command::new("gg-test-command-not-exists")).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -1,25 +1,294 @@
+use std::error::Error as _;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::sync::Arc;
+use clap::error::ErrorKind;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use thiserror::Error;
-use gg_tui::ep_error;
+use gg_tui::{ep_error, Renderer};
+use crate::output;
+
+/// exit codes of gg itself, commands run by gg keep their own codes
+pub(crate) mod exit_code {
+    pub(crate) const ERROR: i32 = 1;
+    // 2 is used by clap for an invalid command line
+    pub(crate) const CONFIG: i32 = 3;
+    pub(crate) const GIT: i32 = 4;
+    pub(crate) const VERSION_MISMATCH: i32 = 5;
+    /// files managed by gg, e.g. the extension registry or the run history
+    pub(crate) const IO: i32 = 6;
+    pub(crate) const DOWNLOAD: i32 = 7;
+    pub(crate) const CHECKSUM: i32 = 8;
+    pub(crate) const ARCHIVE: i32 = 9;
+    /// like shells, a command is found but cannot be executed
+    pub(crate) const SPAWN: i32 = 126;
+    /// like shells, a command is not found
+    pub(crate) const NOT_FOUND: i32 = 127;
+}
 
 #[derive(Error, Debug)]
 pub(crate) enum Error {
     #[error("clap error")]
     Clap(#[from] clap::Error),
+    /// the config file exists but cannot be loaded
+    #[error("failed to load config")]
+    Config(#[source] Arc<gg_config::Error>),
+    /// the config is loaded but a value in it cannot be used, e.g. an unknown path preset
+    #[error("{message}")]
+    InvalidConfig {
+        message: String,
+        hint: Option<String>,
+    },
+    #[error("script '{name}' not found")]
+    ScriptNotFound {
+        name: String,
+        hint: Option<String>,
+    },
+    /// not a builtin subcommand, script, alias, extension or tool
+    #[error("unknown subcommand {name}")]
+    UnknownSubcommand {
+        name: String,
+        hint: Option<String>,
+    },
+    #[error("failed to execute {program}")]
+    Spawn {
+        program: String,
+        #[source]
+        source: io::Error,
+    },
+    /// `action` says what gg was doing, e.g. `failed to clone <url>`
+    #[error("{action}")]
+    Git {
+        action: String,
+        #[source]
+        source: gg_git::Error,
+    },
+    #[error("{what} {installed} is installed but {required} is required")]
+    VersionMismatch {
+        /// e.g. `extension foo`
+        what: String,
+        installed: String,
+        required: String,
+        hint: Option<String>,
+    },
+    /// a pinned tool has no download for the current platform
+    #[error("tool {name} {version} is not available for {platform}")]
+    ToolUnavailable {
+        name: String,
+        version: String,
+        platform: String,
+    },
+    /// `what` is e.g. `tool foo`, or `source of extension foo`
+    #[error("{what} is not declared in config")]
+    NotDeclared {
+        what: String,
+        hint: Option<String>,
+    },
+    /// `what` is e.g. `extension foo`
+    #[error("{what} is not installed")]
+    NotInstalled {
+        what: String,
+        hint: Option<String>,
+    },
+    #[error("failed to build extension in {}, {status}", root.display())]
+    Build {
+        root: PathBuf,
+        status: ExitStatus,
+    },
+    /// e.g. the `gg` requirement of the config
+    #[error("invalid version requirement '{requirement}' of {what}")]
+    VersionRequirement {
+        what: String,
+        requirement: String,
+        #[source]
+        source: semver::Error,
+    },
+    /// `action` says what gg was doing, e.g. `cannot write <path>`
+    #[error("{action}")]
+    Io {
+        action: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("failed to download {url}")]
+    Download {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("checksum mismatch for {url}: expected sha256 {expected}, got {actual}")]
+    Checksum {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("cannot extract {name}")]
+    Archive {
+        name: String,
+        #[source]
+        source: io::Error,
+    },
+    /// reading or writing the run history
+    #[error("{action}")]
+    History {
+        action: String,
+        #[source]
+        source: io::Error,
+    },
     #[error("{0}")]
     Err(String),
+    /// a command run by gg failed, gg exits with the same code without printing anything
+    #[error("exited with code {0}")]
+    Exit(i32),
 }
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
 impl Error {
+    /// stable identifier of the kind of error, for `--output json`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Clap(_) => "usage",
+            Error::Config(_) => "config",
+            Error::InvalidConfig { .. } => "invalid_config",
+            Error::ScriptNotFound { .. } => "script_not_found",
+            Error::UnknownSubcommand { .. } => "unknown_subcommand",
+            Error::Spawn { .. } => "spawn",
+            Error::Git { .. } => "git",
+            Error::VersionMismatch { .. } => "version_mismatch",
+            Error::ToolUnavailable { .. } => "tool_unavailable",
+            Error::NotDeclared { .. } => "not_declared",
+            Error::NotInstalled { .. } => "not_installed",
+            Error::Build { .. } => "build",
+            Error::VersionRequirement { .. } => "version_requirement",
+            Error::Io { .. } => "io",
+            Error::Download { .. } => "download",
+            Error::Checksum { .. } => "checksum",
+            Error::Archive { .. } => "archive",
+            Error::History { .. } => "history",
+            Error::Err(_) => "error",
+            Error::Exit(_) => "exit",
+        }
+    }
+
+    /// what the user may do about the error, may span multiple lines
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            Error::Config(err) => match err.as_ref() {
+                gg_config::Error::Json(_) => Some("fix the config file, or use --config to load another one".to_string()),
+                _ => None,
+            },
+            Error::InvalidConfig { hint, .. }
+            | Error::NotDeclared { hint, .. }
+            | Error::NotInstalled { hint, .. }
+            | Error::ScriptNotFound { hint, .. }
+            | Error::UnknownSubcommand { hint, .. }
+            | Error::VersionMismatch { hint, .. } => hint.clone(),
+            Error::Spawn { program, source } if source.kind() == io::ErrorKind::NotFound => {
+                Some(format!("check that {} is installed and in PATH", program))
+            }
+            Error::Git { source, .. } if source.is_git_not_found() => Some("install git, or add it to PATH".to_string()),
+            Error::ToolUnavailable { name, platform, .. } => {
+                Some(format!("add a download for {} to tools.{}.platforms", platform, name))
+            }
+            Error::Checksum { .. } => Some("the file may be corrupted or changed upstream, update sha256 in the config only if the new file is trusted".to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Clap(e) => e.exit_code(),
+            Error::Config(_) | Error::InvalidConfig { .. } | Error::ToolUnavailable { .. } => exit_code::CONFIG,
+            Error::ScriptNotFound { .. }
+            | Error::UnknownSubcommand { .. }
+            | Error::NotDeclared { .. }
+            | Error::NotInstalled { .. } => exit_code::NOT_FOUND,
+            Error::Spawn { source, .. } if source.kind() == io::ErrorKind::NotFound => exit_code::NOT_FOUND,
+            Error::Spawn { .. } => exit_code::SPAWN,
+            Error::Git { .. } => exit_code::GIT,
+            Error::VersionMismatch { .. } => exit_code::VERSION_MISMATCH,
+            Error::VersionRequirement { .. } => exit_code::CONFIG,
+            Error::Build { .. } => exit_code::ERROR,
+            Error::Io { .. } | Error::History { .. } => exit_code::IO,
+            Error::Download { .. } => exit_code::DOWNLOAD,
+            Error::Checksum { .. } => exit_code::CHECKSUM,
+            Error::Archive { .. } => exit_code::ARCHIVE,
+            Error::Err(_) => exit_code::ERROR,
+            Error::Exit(code) => *code,
+        }
+    }
+
+    /// messages of the underlying errors, outermost first
+    fn causes(&self) -> Vec<String> {
+        let mut causes = Vec::new();
+        let mut source = self.source();
+        while let Some(err) = source {
+            causes.push(err.to_string());
+            source = err.source();
+        }
+        causes
+    }
+
     pub fn exit(&self) -> ! {
         match self {
+            // help and version are printed as is
+            Error::Clap(e) if matches!(e.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) => e.exit(),
+            Error::Exit(code) => std::process::exit(*code),
+            _ if output::is_json() => output::eprint_json(self),
             Error::Clap(e) => e.exit(),
-            Error::Err(e) => {
-                ep_error!("{}", e);
-                std::process::exit(1);
+            _ => {
+                // `caused by:` lines, then the hint
+                let theme = gg_tui::theme();
+                let hint = self.hint();
+                let hint_lines = hint.iter().flat_map(|hint| hint.lines()).enumerate()
+                    .map(|(i, line)| (&theme.hint, if i == 0 { "hint:" } else { "" }, line.to_string()));
+                let details: Vec<_> = self.causes().into_iter()
+                    .map(|cause| (&theme.dim, "caused by:", cause))
+                    .chain(hint_lines)
+                    .collect();
+
+                if gg_tui::terminal().renderer() == Renderer::GithubActions {
+                    // an annotation holds a single message
+                    let mut message = self.to_string();
+                    for (_, label, text) in &details {
+                        message.push_str(&format!("\n  {:5} {}", label, text));
+                    }
+                    ep_error!("{}", message);
+                } else {
+                    ep_error!("{}", self);
+                    for (style, label, text) in details {
+                        eprintln!("  {} {}", style.clone().for_stderr().apply_to(format!("{:5}", label)), text);
+                    }
+                }
             }
         }
+        std::process::exit(self.exit_code());
+    }
+}
+
+/// `{"code": ..., "message": ..., "hint": ..., "causes": [...], "exit_code": ...}`
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let message = match self {
+            // the rendered clap error is `error: <message>`, followed by usage
+            Error::Clap(e) => {
+                let rendered = e.to_string();
+                let first = rendered.lines().next().unwrap_or_default();
+                first.strip_prefix("error: ").unwrap_or(first).to_string()
+            }
+            e => e.to_string(),
+        };
+
+        let mut state = serializer.serialize_struct("Error", 5)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &message)?;
+        state.serialize_field("hint", &self.hint())?;
+        state.serialize_field("causes", &self.causes())?;
+        state.serialize_field("exit_code", &self.exit_code())?;
+        state.end()
     }
 }
 
@@ -36,4 +305,51 @@ pub(crate) fn exit<T>(result: Result<T>) -> ! {
             e.exit();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_string(&error("no script specified")).unwrap();
+        assert_eq!(json, r#"{"code":"error","message":"no script specified","hint":null,"causes":[],"exit_code":1}"#);
+
+        let err = crate::get_cmd().try_get_matches_from(["gg", "--no-such-flag"]).unwrap_err();
+        let json = serde_json::to_value(Error::from(err)).unwrap();
+        assert_eq!(json["code"], "usage");
+        assert_eq!(json["message"], "unexpected argument '--no-such-flag' found");
+        assert_eq!(json["exit_code"], 2);
+    }
+
+    #[test]
+    fn test_causes() {
+        let err = Error::Spawn {
+            program: "cargo".to_string(),
+            source: io::Error::new(io::ErrorKind::NotFound, "No such file or directory"),
+        };
+        assert_eq!(err.to_string(), "failed to execute cargo");
+        assert_eq!(err.causes(), vec!["No such file or directory"]);
+        assert_eq!(err.hint().as_deref(), Some("check that cargo is installed and in PATH"));
+        assert_eq!(err.exit_code(), exit_code::NOT_FOUND);
+
+        let err = Error::Spawn {
+            program: "./build.sh".to_string(),
+            source: io::Error::new(io::ErrorKind::PermissionDenied, "Permission denied"),
+        };
+        assert_eq!(err.hint(), None);
+        assert_eq!(err.exit_code(), exit_code::SPAWN);
+
+        let err = Error::History {
+            action: "cannot read history.jsonl".to_string(),
+            source: io::Error::new(io::ErrorKind::PermissionDenied, "Permission denied"),
+        };
+        assert_eq!(err.causes(), vec!["Permission denied"]);
+        assert_eq!(err.exit_code(), exit_code::IO);
+
+        let err = Error::Config(Arc::new(gg_config::parse("{").err().unwrap()));
+        assert_eq!(err.causes().len(), 2);
+        assert_eq!(err.exit_code(), exit_code::CONFIG);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::alias::Aliases;
+use crate::{builtin_subcommands, extension, Context};
+use crate::result::Error;
+
+/// candidates within this edit distance (and a third of the name's length) are suggested
+const MAX_DISTANCE: usize = 3;
+const MAX_SUGGESTIONS: usize = 3;
+
+/// names among candidates that are close to `name`, closest first
+pub(crate) fn similar<'a, I>(name: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = (name.chars().count() / 3).clamp(1, MAX_DISTANCE);
+
+    let mut similar: Vec<(usize, &str)> = candidates.into_iter()
+        .map(|candidate| (strsim::osa_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    similar.sort();
+    similar.dedup();
+
+    similar.into_iter().take(MAX_SUGGESTIONS).map(|(_, candidate)| candidate.to_string()).collect()
+}
+
+/// error for an unknown subcommand, with suggestions from everything `gg <name>` can run
+pub(crate) fn unknown_subcommand(name: &str, ctx: &Context) -> Error {
+    let aliases = Aliases::new(&ctx.config, &builtin_subcommands());
+    let mut candidates = builtin_subcommands();
+    candidates.extend(ctx.config.scripts.keys().cloned());
+    candidates.extend(ctx.config.aliases.keys().chain(ctx.config.scripts.values().flat_map(|s| &s.aliases))
+        .filter(|alias| aliases.get(alias).is_some())
+        .cloned());
+    candidates.extend(extension::list_available());
+    candidates.extend(ctx.config.tools.keys().cloned());
+
+    Error::UnknownSubcommand {
+        name: name.to_string(),
+        hint: Some(hint(&similar(name, candidates.iter().map(|s| s.as_str())), ctx)),
+    }
+}
+
+/// error for an unknown script, with suggestions from scripts and their aliases
+pub(crate) fn unknown_script(name: &str, ctx: &Context) -> Error {
+    let aliases = Aliases::new(&ctx.config, &builtin_subcommands());
+    let candidates = ctx.config.scripts.keys()
+        .chain(ctx.config.aliases.keys().chain(ctx.config.scripts.values().flat_map(|s| &s.aliases))
+            .filter(|alias| aliases.get(alias).is_some()));
+
+    Error::ScriptNotFound {
+        name: name.to_string(),
+        hint: Some(hint(&similar(name, candidates.map(|s| s.as_str())), ctx)),
+    }
+}
+
+/// suggestions, then where scripts come from
+fn hint(suggestions: &[String], ctx: &Context) -> String {
+    let mut lines = Vec::new();
+
+    match suggestions {
+        [] => {}
+        [only] => lines.push(format!("did you mean {}?", only)),
+        _ => lines.push(format!("did you mean one of {}?", suggestions.join(", "))),
+    }
+
+    match &ctx.config_path {
+        Some(path) => lines.push(format!("scripts are loaded from {}", path.display())),
+        None => lines.push(format!("no config file is found in {}", ctx.work_dir().display())),
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similar() {
+        let candidates = ["test", "build", "bench", "tests", "run", "ext"];
+
+        assert_eq!(similar("tset", candidates), vec!["test"]);
+        assert_eq!(similar("tests", candidates), vec!["tests", "test"]);
+        assert_eq!(similar("biuld", candidates), vec!["build"]);
+        assert_eq!(similar("rn", candidates), vec!["run"]);
+        assert!(similar("deploy", candidates).is_empty());
+    }
+}
@@ -0,0 +1,381 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use cargo_util::ProcessBuilder;
+use sha2::{Digest, Sha256};
+use gg_config::{Tool, ToolDownload};
+use gg_tui::{ep_info, ep_warning, progress};
+use tracing::debug;
+use crate::archive;
+use crate::Context;
+use crate::paths::data_dir;
+use crate::result::{error, Error, Result};
+
+/// platform key of `Tool::platforms` for the current machine, e.g. `linux-x86_64`
+pub(crate) fn platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// a tool resolved for the current platform
+pub(crate) struct ResolvedTool<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+    pub download: &'a ToolDownload,
+    /// the download url with `{version}` substituted
+    pub url: String,
+}
+
+/// whether the tool has a download for the current platform
+pub(crate) fn is_available(tool: &Tool) -> bool {
+    tool.platforms.contains_key(&platform())
+}
+
+pub(crate) fn resolve<'a>(name: &'a str, tool: &'a Tool) -> Result<ResolvedTool<'a>> {
+    let platform = platform();
+    let download = tool.platforms.get(&platform)
+        .ok_or_else(|| Error::ToolUnavailable {
+            name: name.to_string(),
+            version: tool.version.clone(),
+            platform: platform.clone(),
+        })?;
+
+    // the checksum names the entry dir, see `ToolStore::entry_dir`
+    let sha256 = &download.sha256;
+    if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidConfig {
+            message: format!("invalid sha256 {} of tool {} for {}", sha256, name, platform),
+            hint: Some(format!("set tools.{}.platforms.{}.sha256 to the 64 hex digits of the download's sha256", name, platform)),
+        });
+    }
+
+    // the executable must be inside the verified download, see `ToolStore::binary`
+    let (bin, key) = match &download.bin {
+        Some(bin) => (bin.as_str(), "bin"),
+        None => (name, "name"),
+    };
+    let inside = Path::new(bin).components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if bin.is_empty() || !inside {
+        return Err(Error::InvalidConfig {
+            message: format!("invalid {} {} of tool {} for {}", key, bin, name, platform),
+            hint: Some("the executable must be a relative path inside the download, without `..`".to_string()),
+        });
+    }
+
+    Ok(ResolvedTool {
+        name,
+        version: &tool.version,
+        download,
+        url: download.url.replace("{version}", &tool.version),
+    })
+}
+
+/// downloaded tools under `<data dir>/tools`, addressed by the sha256 of the download
+///
+/// Each download is extracted into `sha256/<checksum>`, so tools are shared between
+/// projects pinning the same file and never change once installed.
+pub(crate) struct ToolStore {
+    dir: PathBuf,
+}
+
+impl Default for ToolStore {
+    fn default() -> Self {
+        ToolStore::new(data_dir().join("tools"))
+    }
+}
+
+impl ToolStore {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> ToolStore {
+        ToolStore { dir: dir.into() }
+    }
+
+    fn entry_dir(&self, tool: &ResolvedTool) -> PathBuf {
+        self.dir.join("sha256").join(tool.download.sha256.to_lowercase())
+    }
+
+    /// path of the executable, which may not exist yet
+    ///
+    /// It never leaves the store, as `resolve` only accepts hex checksums, and relative
+    /// paths without `..` for `bin`.
+    pub fn binary(&self, tool: &ResolvedTool) -> PathBuf {
+        let bin = match &tool.download.bin {
+            Some(bin) => bin.clone(),
+            None => format!("{}{}", tool.name, std::env::consts::EXE_SUFFIX),
+        };
+        self.entry_dir(tool).join(bin)
+    }
+
+    pub fn is_installed(&self, tool: &ResolvedTool) -> bool {
+        self.binary(tool).is_file()
+    }
+
+    /// download, verify and extract the tool if not installed yet, return the executable
+    pub async fn install(&self, tool: &ResolvedTool<'_>) -> Result<PathBuf> {
+        let binary = self.binary(tool);
+        if binary.is_file() {
+            return Ok(binary);
+        }
+
+        debug!("downloading {} {} from {}", tool.name, tool.version, tool.url);
+        let url = tool.url.clone();
+        let message = format!("downloading {} {}", tool.name, tool.version);
+        let content = tokio::task::spawn_blocking(move || download(&url, message))
+            .await
+            .map_err(|err| Error::Download { url: tool.url.clone(), source: Box::new(err) })??;
+
+        let checksum = hex::encode(Sha256::digest(&content));
+        if !checksum.eq_ignore_ascii_case(&tool.download.sha256) {
+            return Err(Error::Checksum {
+                url: tool.url.clone(),
+                expected: tool.download.sha256.clone(),
+                actual: checksum,
+            });
+        }
+
+        // prepare in a temporary dir and move into place at last, so a half-done
+        // installation is never seen as installed
+        let tmp = self.dir.join("tmp").join(format!("{}-{}", checksum, std::process::id()));
+        let content_dir = tmp.join("content");
+        let _ = fs::remove_dir_all(&tmp);
+        fs::create_dir_all(&content_dir)
+            .map_err(|source| Error::Io { action: format!("cannot create {}", content_dir.display()), source })?;
+
+        let result = self.unpack(tool, &content, &tmp, &content_dir);
+        let result = result.and_then(|_| {
+            let entry_dir = self.entry_dir(tool);
+            match fs::create_dir_all(entry_dir.parent().unwrap()).and_then(|_| fs::rename(&content_dir, &entry_dir)) {
+                Ok(_) => Ok(()),
+                // installed by another gg process in the meantime, entries never change once installed
+                Err(_) if entry_dir.is_dir() => Ok(()),
+                Err(source) => Err(Error::Io { action: format!("cannot install {} into {}", tool.name, entry_dir.display()), source }),
+            }
+        });
+        let _ = fs::remove_dir_all(&tmp);
+        result?;
+
+        if !binary.is_file() {
+            return Err(error(&format!("executable {} is not found in {}", binary.display(), tool.url)));
+        }
+        Ok(binary)
+    }
+
+    fn unpack(&self, tool: &ResolvedTool, content: &[u8], tmp: &Path, content_dir: &Path) -> Result<()> {
+        let write_error = |path: &Path, source| Error::Io { action: format!("cannot write {}", path.display()), source };
+
+        if archive::is_archive(&tool.url) {
+            let file = tmp.join("download");
+            fs::write(&file, content).map_err(|err| write_error(&file, err))?;
+            return archive::extract(&file, &tool.url, content_dir);
+        }
+
+        // a single executable
+        let binary = content_dir.join(self.binary(tool).file_name().unwrap());
+        fs::write(&binary, content).map_err(|err| write_error(&binary, err))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&binary, fs::Permissions::from_mode(0o755)).map_err(|err| write_error(&binary, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// download with a progress bar showing `message`
+fn download(url: &str, message: String) -> Result<Vec<u8>> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return fs::read(path).map_err(|err| Error::Download { url: url.to_string(), source: Box::new(err) });
+    }
+
+    let response = ureq::get(url)
+        .call()
+        .map_err(|err| Error::Download { url: url.to_string(), source: Box::new(err) })?;
+    let len = response.header("Content-Length").and_then(|len| len.parse().ok());
+
+    let bar = progress::progress_bar(len, message);
+    let mut content = Vec::new();
+    let result = bar.wrap_read(response.into_reader()).read_to_end(&mut content);
+    bar.finish_and_clear();
+    result.map_err(|err| Error::Download { url: url.to_string(), source: Box::new(err) })?;
+
+    Ok(content)
+}
+
+/// install all tools of the config if needed, return the dirs of their executables
+///
+/// Tools not available for the current platform are skipped with a warning, they fail
+/// only when invoked.
+pub(crate) async fn install_all(ctx: &Context) -> Result<Vec<PathBuf>> {
+    let store = ToolStore::default();
+
+    let mut names: Vec<&String> = ctx.config.tools.keys().collect();
+    names.sort();
+
+    let mut dirs = Vec::with_capacity(names.len());
+    for name in names {
+        let tool = &ctx.config.tools[name];
+        if !is_available(tool) {
+            ep_warning!("tool {} {} is not available for {}, skipped", name, tool.version, platform());
+            continue;
+        }
+
+        let tool = resolve(name, tool)?;
+        if !store.is_installed(&tool) {
+            ep_info!("installing {} {}", tool.name, tool.version);
+        }
+
+        let binary = store.install(&tool).await?;
+        dirs.push(binary.parent().unwrap().to_path_buf());
+    }
+
+    Ok(dirs)
+}
+
+/// run a tool pinned by the config in current directory, installing it if needed
+pub(crate) async fn exec(ctx: &Context, name: &str, args: &[OsString]) -> Result<()> {
+    let tool = resolve(name, &ctx.config.tools[name])?;
+    let store = ToolStore::default();
+    if !store.is_installed(&tool) {
+        ep_info!("installing {} {}", tool.name, tool.version);
+    }
+    let binary = store.install(&tool).await?;
+
+    if let Err(err) = ProcessBuilder::new(&binary).args(args).cwd(&ctx.cwd).exec_replace() {
+        // `{:#}` keeps the causes of the anyhow error
+        return Err(Error::Spawn { program: binary.display().to_string(), source: io::Error::other(format!("{:#}", err)) });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::*;
+
+    fn tool(url: String, content: &[u8], bin: Option<&str>) -> Tool {
+        let mut platforms = HashMap::new();
+        platforms.insert(platform(), ToolDownload {
+            url,
+            sha256: hex::encode(Sha256::digest(content)),
+            bin: bin.map(String::from),
+        });
+
+        Tool {
+            version: "1.0.0".to_string(),
+            platforms,
+        }
+    }
+
+    fn setup(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gg-test-tool-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_install_executable() {
+        let dir = setup("executable");
+        let file = dir.join("hello-1.0.0");
+        fs::write(&file, "#!/bin/sh\necho hello\n").unwrap();
+
+        let url = format!("file://{}", dir.join("hello-{version}").display());
+        let tool = tool(url, &fs::read(&file).unwrap(), None);
+        let resolved = resolve("hello", &tool).unwrap();
+        assert_eq!(resolved.url, format!("file://{}", file.display()));
+
+        let store = ToolStore::new(dir.join("store"));
+        assert!(!store.is_installed(&resolved));
+
+        let binary = store.install(&resolved).await.unwrap();
+        assert!(store.is_installed(&resolved));
+        assert_eq!(binary.file_name().unwrap().to_string_lossy(), format!("hello{}", std::env::consts::EXE_SUFFIX));
+        assert!(binary.starts_with(dir.join("store").join("sha256")));
+    }
+
+    #[tokio::test]
+    async fn test_install_archive() {
+        let dir = setup("archive");
+        let archive = dir.join("tool.tar.gz");
+        {
+            let file = fs::File::create(&archive).unwrap();
+            let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+            let content = b"#!/bin/sh\necho hello\n";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o755);
+            builder.append_data(&mut header, "tool/bin/hello", &content[..]).unwrap();
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+
+        let tool = tool(format!("file://{}", archive.display()), &fs::read(&archive).unwrap(), Some("tool/bin/hello"));
+        let store = ToolStore::new(dir.join("store"));
+        let binary = store.install(&resolve("hello", &tool).unwrap()).await.unwrap();
+        assert!(binary.ends_with("tool/bin/hello"));
+        assert!(binary.is_file());
+    }
+
+    #[tokio::test]
+    async fn test_install_checksum_mismatch() {
+        let dir = setup("checksum");
+        let file = dir.join("hello");
+        fs::write(&file, "hello").unwrap();
+
+        let tool = tool(format!("file://{}", file.display()), b"other content", None);
+        let store = ToolStore::new(dir.join("store"));
+        let resolved = resolve("hello", &tool).unwrap();
+
+        let err = store.install(&resolved).await.err().unwrap();
+        assert!(err.to_string().contains("checksum mismatch"), "unexpected error: {}", err);
+        assert!(!store.is_installed(&resolved));
+    }
+
+    #[test]
+    fn test_resolve_bin() {
+        let dir = setup("bin");
+        let url = format!("file://{}", dir.join("hello").display());
+        for bin in ["/bin/sh", "../hello", "tool/../../hello", ""] {
+            let tool = tool(url.clone(), b"hello", Some(bin));
+            assert!(resolve("hello", &tool).is_err(), "{}", bin);
+        }
+
+        // without bin, the executable is named after the tool
+        let unnamed = tool(url.clone(), b"hello", None);
+        assert!(resolve("../../bin/echo", &unnamed).is_err());
+
+        let store = ToolStore::new(dir.join("store"));
+        let nested = tool(url.clone(), b"hello", Some("./tool/bin/hello"));
+        assert!(store.binary(&resolve("hello", &nested).unwrap()).starts_with(dir.join("store")));
+
+        // the checksum is a path component of the store
+        for sha256 in ["../../../../bin", "", "abc", &"g".repeat(64), &format!("{}/..", "a".repeat(61))] {
+            let mut tool = tool(url.clone(), b"hello", None);
+            tool.platforms.get_mut(&platform()).unwrap().sha256 = sha256.to_string();
+            assert!(resolve("hello", &tool).is_err(), "{}", sha256);
+        }
+
+        let unavailable = Tool { version: "1.0.0".to_string(), platforms: HashMap::new() };
+        assert!(!is_available(&unavailable));
+        let err = resolve("hello", &unavailable).err().unwrap();
+        assert_eq!(err.code(), "tool_unavailable");
+        assert_eq!(err.exit_code(), crate::result::exit_code::CONFIG);
+    }
+
+    #[tokio::test]
+    async fn test_install_existing_entry() {
+        let dir = setup("existing");
+        let file = dir.join("hello");
+        fs::write(&file, "#!/bin/sh\necho hello\n").unwrap();
+
+        let tool = tool(format!("file://{}", file.display()), &fs::read(&file).unwrap(), None);
+        let resolved = resolve("hello", &tool).unwrap();
+        let store = ToolStore::new(dir.join("store"));
+        // moved into place by another process, the rename fails but the entry is used as is
+        fs::create_dir_all(store.entry_dir(&resolved)).unwrap();
+        fs::write(store.entry_dir(&resolved).join("README"), "").unwrap();
+
+        let err = store.install(&resolved).await.err().unwrap();
+        assert!(err.to_string().contains("is not found"), "unexpected error: {}", err);
+    }
+}